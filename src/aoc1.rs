@@ -1,73 +1,684 @@
+use crate::error::{parse_number, Error, Overflow, ParseError};
+use crate::external_sort::{ExternalSorter, Merge, RunValue, SortedRuns};
+use crate::parse::{integer_columns, integer_row, non_blank_lines};
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-
-fn read_file(file_path: String) -> (Vec<i32>, Vec<i32>) {
-    let path = Path::new(&file_path);
-
-    let mut col1 = Vec::new();
-    let mut col2 = Vec::new();
-
-    let file = File::open(&path).expect("File not found");
-    let reader = io::BufReader::new(file);
-
-    for line in reader.lines() {
-        let line = line.expect("Unable to read line");
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if let (Some(x), Some(y)) = (parts.get(0), parts.get(1)) {
-            if let (Ok(x), Ok(y)) = (x.parse::<i32>(), y.parse::<i32>()) {
-                col1.push(x);
-                col2.push(y);
+use std::fmt;
+use std::hash::Hash;
+use std::io::BufRead;
+use std::iter::Peekable;
+use std::str::FromStr;
+
+/// Integer types the lists can hold, such as `i32`, `i64`, `u64` or `i128`.
+/// Totals are added up as `i128` and checked, so that they fail with
+/// [`Overflow`] instead of wrapping. That is wider than every type but `i128`
+/// itself, whose totals are only checked: a single `|a - b|` of two `i128`
+/// values can already overflow.
+pub trait ListValue: Copy + Ord + Hash + FromStr + Into<i128> {}
+
+impl<T: Copy + Ord + Hash + FromStr + Into<i128>> ListValue for T {}
+
+const DISTANCE: Overflow = Overflow { total: "the total distance" };
+const SIMILARITY: Overflow = Overflow { total: "the similarity score" };
+
+/// What to do about a line that does not hold exactly two integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy<T> {
+    /// Stop with the line's error.
+    Error,
+    /// Leave out what does not fit.
+    Truncate,
+    /// Fill in this value for what is missing or unreadable.
+    Pad(T),
+}
+
+/// Which column the value of a line with a single one belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoneValue {
+    Left,
+    Right,
+    /// The right column if the line starts with whitespace, as when the left
+    /// value is blank in a file whose columns are padded, otherwise the left.
+    ByIndent,
+}
+
+/// How [`read_lists`] treats malformed lines, which hold something that is
+/// not an integer or more than two values, and lines with a single value,
+/// which would leave the lists with unequal lengths:
+///
+/// - `Error` stops at the line.
+/// - `Truncate` skips the line, so both lists stay the same length.
+/// - `Pad(v)` puts `v` in place of every unreadable value of a malformed line,
+///   dropping values past the second, and in place of the missing value of a
+///   line with one.
+///
+/// `lone` says which column a single value is in: it is kept there when
+/// padding and named in the error otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadPolicy<T> {
+    pub malformed: Policy<T>,
+    pub unequal: Policy<T>,
+    pub lone: LoneValue,
+}
+
+impl<T> Default for ReadPolicy<T> {
+    fn default() -> Self {
+        ReadPolicy {
+            malformed: Policy::Error,
+            unequal: Policy::Error,
+            lone: LoneValue::Left,
+        }
+    }
+}
+
+/// The two lists, along with every line a [`ReadPolicy`] let through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lists<T> {
+    pub left: Vec<T>,
+    pub right: Vec<T>,
+    /// Malformed lines that were skipped or padded.
+    pub malformed: Vec<ParseError>,
+    /// Lines with a single value that were skipped or padded.
+    pub mismatched: Vec<ParseError>,
+}
+
+/// Reads the two columns, handling lines that do not hold two integers as
+/// `policy` says. The lists always come out with the same length.
+pub fn read_lists<T: ListValue>(input: &str, policy: ReadPolicy<T>) -> Result<Lists<T>, ParseError> {
+    let mut lists = Lists {
+        left: Vec::new(),
+        right: Vec::new(),
+        malformed: Vec::new(),
+        mismatched: Vec::new(),
+    };
+
+    for (line_no, line) in non_blank_lines(input) {
+        let mut values = Vec::with_capacity(2);
+        let mut problem = None;
+        for (index, token) in line.split_whitespace().enumerate() {
+            let value = if index < 2 {
+                parse_number(line_no, line, token)
+            } else {
+                Err(ParseError::at(line_no, line, token, "end of line"))
+            };
+            match (value, policy.malformed) {
+                (Ok(value), _) => values.push(value),
+                (Err(err), Policy::Error) => return Err(err),
+                (Err(err), policy) => {
+                    if let Policy::Pad(pad) = policy {
+                        if index < 2 {
+                            values.push(pad);
+                        }
+                    }
+                    problem.get_or_insert(err);
+                }
             }
         }
+        if let Some(err) = problem {
+            lists.malformed.push(err);
+            if policy.malformed == Policy::Truncate {
+                continue;
+            }
+        }
+
+        match values[..] {
+            [left, right] => {
+                lists.left.push(left);
+                lists.right.push(right);
+            }
+            [value] => {
+                let missing_left = match policy.lone {
+                    LoneValue::Left => false,
+                    LoneValue::Right => true,
+                    LoneValue::ByIndent => line.starts_with(char::is_whitespace),
+                };
+                let err = if missing_left {
+                    ParseError::new(line_no, 1, "column 1", "blank")
+                } else {
+                    ParseError::end_of_line(line_no, line, "column 2")
+                };
+                match policy.unequal {
+                    Policy::Error => return Err(err),
+                    Policy::Truncate => {}
+                    Policy::Pad(pad) if missing_left => {
+                        lists.left.push(pad);
+                        lists.right.push(value);
+                    }
+                    Policy::Pad(pad) => {
+                        lists.left.push(value);
+                        lists.right.push(pad);
+                    }
+                }
+                lists.mismatched.push(err);
+            }
+            _ => unreachable!("A non-blank line that is not skipped has one or two values"),
+        }
     }
 
-    (col1, col2)
+    Ok(lists)
 }
 
-fn return_dist(l1: &Vec<i32>, l2: &Vec<i32>) -> i32 {
-    let mut sorted_l1 = l1.clone();
-    let mut sorted_l2 = l2.clone();
+/// Reads the two columns, failing at any line that does not hold two integers.
+pub fn read_file<T: ListValue>(input: &str) -> Result<(Vec<T>, Vec<T>), ParseError> {
+    let lists = read_lists(input, ReadPolicy::default())?;
+    Ok((lists.left, lists.right))
+}
 
-    sorted_l1.sort();
-    sorted_l2.sort();
+/// `|a - b|`, added to `total`.
+fn add_distance(total: i128, a: impl Into<i128>, b: impl Into<i128>) -> Result<i128, Overflow> {
+    a.into()
+        .checked_sub(b.into())
+        .and_then(i128::checked_abs)
+        .and_then(|dist| total.checked_add(dist))
+        .ok_or(DISTANCE)
+}
 
-    let mut dist = 0;
-    for (i1, i2) in sorted_l1.iter().zip(sorted_l2.iter()) {
-        dist += (i1 - i2).abs();
+/// `value * left_count * right_count`, added to `total`.
+fn add_similarity(total: i128, value: impl Into<i128>, left_count: usize, right_count: usize) -> Result<i128, Overflow> {
+    value
+        .into()
+        .checked_mul(left_count as i128)
+        .and_then(|curr| curr.checked_mul(right_count as i128))
+        .and_then(|term| total.checked_add(term))
+        .ok_or(SIMILARITY)
+}
+
+/// Why two lists have no distance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistanceError {
+    /// The lists differ in length, so some values have nothing to pair up with.
+    Unequal { left: usize, right: usize },
+    Overflow(Overflow),
+}
+
+impl fmt::Display for DistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistanceError::Unequal { left, right } => {
+                write!(f, "the lists hold {} and {} values, which cannot be paired up", left, right)
+            }
+            DistanceError::Overflow(err) => write!(f, "{}", err),
+        }
     }
-    dist
 }
 
-fn similarity_score(l1: &Vec<i32>, l2: &Vec<i32>) -> i32{
-    let mut c1 = HashMap::new();
-    let mut c2 = HashMap::new();
+impl std::error::Error for DistanceError {}
 
-    for num in l1 {
-        *c1.entry(num).or_insert(0) += 1;
+impl From<Overflow> for DistanceError {
+    fn from(err: Overflow) -> Self {
+        DistanceError::Overflow(err)
     }
-    for num in l2 {
-        *c2.entry(num).or_insert(0) += 1;
+}
+
+fn check_lengths(left: usize, right: usize) -> Result<(), DistanceError> {
+    if left != right {
+        return Err(DistanceError::Unequal { left, right });
     }
+    Ok(())
+}
 
-    let mut similarity: i32 = 0;
+/// Pairs up the smallest values of both lists, then the next smallest and so
+/// on, and adds up how far apart each pair is. Lists of different lengths are
+/// an error, see [`return_dist_truncated`] to pair up what can be.
+pub fn return_dist<T: ListValue>(l1: &[T], l2: &[T]) -> Result<i128, DistanceError> {
+    check_lengths(l1.len(), l2.len())?;
+    Ok(sorted_distance(&sorted(l1), &sorted(l2))?)
+}
+
+/// Like [`return_dist`], but pairs up only as many values as the shorter list
+/// has, leaving out the largest values of the longer one.
+pub fn return_dist_truncated<T: ListValue>(l1: &[T], l2: &[T]) -> Result<i128, Overflow> {
+    sorted_distance(&sorted(l1), &sorted(l2))
+}
+
+pub fn similarity_score<T: ListValue>(l1: &[T], l2: &[T]) -> Result<i128, Overflow> {
+    weighted_overlap(&counts(l1), &counts(l2))
+}
 
-    for (&k, &v) in &c1 {
-        let curr = k * v;
+fn sorted<T: ListValue>(list: &[T]) -> Vec<T> {
+    let mut sorted = list.to_vec();
+    sorted.sort();
+    sorted
+}
+
+fn counts<T: ListValue>(list: &[T]) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for &num in list {
+        *counts.entry(num).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The distance of two lists that are already sorted, pairing up as many
+/// values as the shorter one has.
+fn sorted_distance<T: ListValue>(sorted_l1: &[T], sorted_l2: &[T]) -> Result<i128, Overflow> {
+    let mut dist = 0;
+    for (&i1, &i2) in sorted_l1.iter().zip(sorted_l2.iter()) {
+        dist = add_distance(dist, i1, i2)?;
+    }
+    Ok(dist)
+}
+
+/// The similarity of two lists given how often each value occurs in them.
+fn weighted_overlap<T: ListValue>(c1: &HashMap<T, usize>, c2: &HashMap<T, usize>) -> Result<i128, Overflow> {
+    let mut similarity = 0;
+
+    for (&k, &v) in c1 {
         if let Some(&count_in_c2) = c2.get(&k) {
-            similarity += curr * count_in_c2;
+            similarity = add_similarity(similarity, k, v, count_in_c2)?;
+        }
+    }
+
+    Ok(similarity)
+}
+
+/// Reads any number of whitespace-separated columns, as many as the first
+/// non-blank line has. An input without any is an error.
+pub fn read_columns<T: ListValue>(input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+    let Some((_, first)) = non_blank_lines(input).next() else {
+        return Err(ParseError::new(input.lines().count() + 1, 1, "a row of integers", "end of input"));
+    };
+    integer_columns(input, first.split_whitespace().count())
+}
+
+/// The distance and the similarity of every pair of columns. Both are
+/// symmetric, so entry `[i][j]` equals entry `[j][i]`; on the diagonal the
+/// distance is 0 and the similarity compares a column with itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrices {
+    pub distance: Vec<Vec<i128>>,
+    pub similarity: Vec<Vec<i128>>,
+}
+
+/// Compares every pair of `columns`, which must all have the same length,
+/// sorting and counting each column once.
+pub fn pairwise<T: ListValue>(columns: &[Vec<T>]) -> Result<Matrices, DistanceError> {
+    if let Some(first) = columns.first() {
+        for column in columns {
+            check_lengths(first.len(), column.len())?;
         }
     }
+    let sorted: Vec<Vec<T>> = columns.iter().map(|column| sorted(column)).collect();
+    let counts: Vec<HashMap<T, usize>> = columns.iter().map(|column| counts(column)).collect();
 
-    similarity
+    let n = columns.len();
+    let mut matrices = Matrices {
+        distance: vec![vec![0; n]; n],
+        similarity: vec![vec![0; n]; n],
+    };
+    for i in 0..n {
+        for j in i..n {
+            let distance = sorted_distance(&sorted[i], &sorted[j])?;
+            let similarity = weighted_overlap(&counts[i], &counts[j])?;
+            for (a, b) in [(i, j), (j, i)] {
+                matrices.distance[a][b] = distance;
+                matrices.similarity[a][b] = similarity;
+            }
+        }
+    }
+    Ok(matrices)
+}
+
+impl Matrices {
+    /// Both matrices as right-aligned text tables, with 1-based column names.
+    pub fn table(&self) -> String {
+        [("distance", &self.distance), ("similarity", &self.similarity)]
+            .into_iter()
+            .map(|(name, matrix)| {
+                let headers: Vec<String> = (1..=matrix.len()).map(|i| format!("col {}", i)).collect();
+                let width = matrix
+                    .iter()
+                    .flatten()
+                    .map(|value| value.to_string().len())
+                    .chain(headers.iter().map(String::len))
+                    .chain([name.len()])
+                    .max()
+                    .unwrap_or(0);
+                let mut table = format!("{:<width$}", name, width = width);
+                for header in &headers {
+                    table.push_str(&format!("  {:>width$}", header, width = width));
+                }
+                for (header, row) in headers.iter().zip(matrix) {
+                    table.push_str(&format!("\n{:<width$}", header, width = width));
+                    for value in row {
+                        table.push_str(&format!("  {:>width$}", value, width = width));
+                    }
+                }
+                table
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        fn rows(matrix: &[Vec<i128>]) -> String {
+            let rows: Vec<String> = matrix
+                .iter()
+                .map(|row| {
+                    let values: Vec<String> = row.iter().map(i128::to_string).collect();
+                    format!("[{}]", values.join(", "))
+                })
+                .collect();
+            format!("[{}]", rows.join(", "))
+        }
+
+        format!(
+            "{{\"columns\": {}, \"distance\": {}, \"similarity\": {}}}",
+            self.distance.len(),
+            rows(&self.distance),
+            rows(&self.similarity)
+        )
+    }
 }
 
-pub fn run() {
-    let file_path: String = "data/aoc1.txt".to_string();
-    let (col1, col2) = read_file(file_path);
-    println!("Solution for Problem 1 : ");
-    println!("Part 1 : {}", return_dist(&col1, &col2));
-    println!("Part 2 : {}", similarity_score(&col1, &col2));
-    println!("***************");
-}
\ No newline at end of file
+/// Both answers for a pair of lists too large for memory, read line by line
+/// from `reader` (called `source` in errors). Each column is sorted in chunks
+/// of at most `chunk_len` values spilled to temporary files, and the answers
+/// come from merging the spilled runs, without ever holding the lists.
+pub fn stream<T: ListValue + RunValue>(
+    reader: impl BufRead,
+    source: &str,
+    chunk_len: usize,
+) -> Result<(i128, i128), Error> {
+    let mut left = ExternalSorter::new(chunk_len)?;
+    let mut right = ExternalSorter::new(chunk_len)?;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|err| Error::Io {
+            path: source.to_string(),
+            source: err,
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: Vec<T> = integer_row((index + 1, &line), 2).map_err(|err| Error::Parse {
+            path: source.to_string(),
+            source: err,
+        })?;
+        left.push(row[0])?;
+        right.push(row[1])?;
+    }
+
+    let (left, right) = (left.finish()?, right.finish()?);
+    merge_answers(&left, &right, source)
+}
+
+/// The next value of a sorted stream together with how often it repeats.
+fn next_group<T: RunValue>(merge: &mut Peekable<Merge<'_, T>>) -> Result<Option<(T, usize)>, Error> {
+    let Some(value) = merge.next().transpose()? else {
+        return Ok(None);
+    };
+    let mut count = 1;
+    while let Some(Ok(next)) = merge.peek() {
+        if *next != value {
+            break;
+        }
+        merge.next();
+        count += 1;
+    }
+    Ok(Some((value, count)))
+}
+
+/// How often `value` occurs, moving `group` on to the next one if it holds
+/// `value`, or 0 if it does not.
+fn take_group<T: RunValue>(
+    group: &mut Option<(T, usize)>,
+    merge: &mut Peekable<Merge<'_, T>>,
+    value: T,
+) -> Result<usize, Error> {
+    match *group {
+        Some((v, count)) if v == value => {
+            *group = next_group(merge)?;
+            Ok(count)
+        }
+        _ => Ok(0),
+    }
+}
+
+/// The distance and similarity of two sorted columns of equal length, in one
+/// pass over their merged runs with a single cursor per column. Both come from
+/// walking the distinct values of either column in ascending order: the
+/// similarity joins equal values, and the distance is the area between the
+/// columns' counting functions, the sum of `|D| * gap` where `D` is how many
+/// more left values than right ones lie below the gap. That adds up the same
+/// `|l - r|` as pairing the columns up in order.
+fn merge_answers<T: ListValue + RunValue>(
+    left: &SortedRuns<T>,
+    right: &SortedRuns<T>,
+    source: &str,
+) -> Result<(i128, i128), Error> {
+    let overflow = |err| Error::Overflow {
+        path: source.to_string(),
+        source: err,
+    };
+    let mut left_runs = left.merged()?.peekable();
+    let mut right_runs = right.merged()?.peekable();
+    let mut left_group = next_group(&mut left_runs)?;
+    let mut right_group = next_group(&mut right_runs)?;
+
+    let mut distance: i128 = 0;
+    let mut similarity = 0;
+    let mut surplus: i128 = 0;
+    let mut previous: Option<T> = None;
+    loop {
+        let value = match (left_group, right_group) {
+            (Some((l, _)), Some((r, _))) => l.min(r),
+            (Some((l, _)), None) => l,
+            (None, Some((r, _))) => r,
+            (None, None) => break,
+        };
+        if let Some(previous) = previous {
+            distance = (value.into() - previous.into())
+                .checked_mul(surplus.abs())
+                .and_then(|area| distance.checked_add(area))
+                .ok_or(DISTANCE)
+                .map_err(overflow)?;
+        }
+
+        let l_count = take_group(&mut left_group, &mut left_runs, value)?;
+        let r_count = take_group(&mut right_group, &mut right_runs, value)?;
+        similarity = add_similarity(similarity, value, l_count, r_count).map_err(overflow)?;
+        surplus += l_count as i128 - r_count as i128;
+        previous = Some(value);
+    }
+
+    Ok((distance, similarity))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    /// `i128`, so that exports of any integer type up to `u64` can be solved.
+    type Input = (Vec<i128>, Vec<i128>);
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        read_file(raw)
+    }
+
+    fn part_one(&self, (col1, col2): &Self::Input) -> Result<Answer, Overflow> {
+        match return_dist(col1, col2) {
+            Ok(dist) => Ok(dist.into()),
+            Err(DistanceError::Overflow(err)) => Err(err),
+            Err(err @ DistanceError::Unequal { .. }) => unreachable!("read_file pairs every value: {}", err),
+        }
+    }
+
+    fn part_two(&self, (col1, col2): &Self::Input) -> Result<Answer, Overflow> {
+        similarity_score(col1, col2).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{assert_example, Part};
+
+    const EXAMPLE: &str = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+    #[test]
+    fn parses_example() {
+        let (col1, col2) = read_file::<i32>(EXAMPLE).unwrap();
+        assert_eq!(col1, vec![3, 4, 2, 1, 3, 3]);
+        assert_eq!(col2, vec![4, 3, 5, 3, 9, 3]);
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day1, EXAMPLE, Part::One, 11);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day1, EXAMPLE, Part::Two, 31);
+    }
+
+    #[test]
+    fn streams_example() {
+        assert_eq!(stream::<i64>(EXAMPLE.as_bytes(), "example", 2).unwrap(), (11, 31));
+    }
+
+    #[test]
+    fn streams_like_in_memory() {
+        let input: String = (0..500)
+            .map(|i: i32| format!("{}   {}\n", (i * 37) % 101 - 40, (i * 53) % 89))
+            .collect();
+        let (col1, col2) = read_file::<i64>(&input).unwrap();
+        // 72 runs per column, more than are merged at once.
+        assert_eq!(
+            stream::<i64>(input.as_bytes(), "generated", 7).unwrap(),
+            (return_dist(&col1, &col2).unwrap(), similarity_score(&col1, &col2).unwrap())
+        );
+
+        let err = stream::<i64>("3   4\n4   x\n".as_bytes(), "bad", 64).unwrap_err();
+        assert_eq!(err.to_string(), "bad:2:5: expected an integer, found `x`");
+    }
+
+    #[test]
+    fn works_with_wide_and_unsigned_types() {
+        let (col1, col2) = read_file::<u64>("18446744073709551615   0\n0   1\n").unwrap();
+        assert_eq!(return_dist(&col1, &col2), Ok(u64::MAX as i128 - 1));
+        assert_eq!(similarity_score(&col1, &col2), Ok(0));
+
+        let big = i64::MAX as i128 * 4;
+        assert_eq!(similarity_score(&[big, big], &[big]), Ok(big * 2));
+        assert!(read_file::<u64>("-1   2\n").is_err());
+    }
+
+    #[test]
+    fn handles_values_above_i64_max() {
+        let input = "18446744073709551615   9223372036854775808\n1   18446744073709551615\n";
+        let answers = (u64::MAX as i128 - (1 << 63), u64::MAX as i128);
+        assert_eq!(stream::<u64>(input.as_bytes(), "wide", 1).unwrap(), answers);
+        assert_example(&Day1, input, Part::One, answers.0);
+        assert_example(&Day1, input, Part::Two, answers.1);
+    }
+
+    #[test]
+    fn reports_overflow() {
+        let err = return_dist(&[i128::MAX, 0], &[i128::MIN, 0]).unwrap_err();
+        assert_eq!(err.to_string(), "the total distance overflowed");
+        assert!(similarity_score(&[i128::MAX / 2], &[i128::MAX / 2]).is_ok());
+        assert_eq!(similarity_score(&[i128::MAX / 2; 3], &[i128::MAX / 2]), Err(SIMILARITY));
+    }
+
+    #[test]
+    fn pairs_up_only_lists_of_equal_length() {
+        assert_eq!(return_dist(&[3, 1, 7], &[2, 4]), Err(DistanceError::Unequal { left: 3, right: 2 }));
+        assert_eq!(return_dist_truncated(&[3, 1, 7], &[2, 4]), Ok(2));
+        assert_eq!(
+            pairwise(&[vec![1, 2], vec![1, 2], vec![3]]),
+            Err(DistanceError::Unequal { left: 2, right: 1 })
+        );
+    }
+
+    const DAMAGED: &str = "\
+3   4
+4   x
+2
+    5
+1   3   7
+";
+
+    #[test]
+    fn errors_on_damaged_lines_by_default() {
+        let err = read_lists::<i64>("3   4\n2\n", ReadPolicy::default()).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 2, "column 2"));
+        let policy = ReadPolicy { malformed: Policy::Truncate, ..ReadPolicy::default() };
+        let err = read_lists::<i64>(DAMAGED, policy).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (3, "column 2"));
+        let policy = ReadPolicy { lone: LoneValue::Right, ..ReadPolicy::default() };
+        let err = read_lists::<i64>("3   4\n2\n", policy).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 1, "column 1"));
+    }
+
+    #[test]
+    fn truncates_damaged_lines() {
+        let policy = ReadPolicy { malformed: Policy::Truncate, unequal: Policy::Truncate, lone: LoneValue::Left };
+        let lists = read_lists::<i64>(DAMAGED, policy).unwrap();
+        assert_eq!((lists.left, lists.right), (vec![3], vec![4]));
+        let lines = |errors: &[ParseError]| errors.iter().map(|err| err.line).collect::<Vec<_>>();
+        assert_eq!(lines(&lists.malformed), vec![2, 5]);
+        assert_eq!(lines(&lists.mismatched), vec![3, 4]);
+    }
+
+    #[test]
+    fn pads_damaged_lines() {
+        let policy = ReadPolicy { malformed: Policy::Pad(0), unequal: Policy::Pad(-1), lone: LoneValue::ByIndent };
+        let lists = read_lists::<i64>(DAMAGED, policy).unwrap();
+        assert_eq!(lists.left, vec![3, 4, 2, -1, 1]);
+        assert_eq!(lists.right, vec![4, 0, -1, 5, 3]);
+        assert_eq!((lists.malformed.len(), lists.mismatched.len()), (2, 2));
+
+        let lists = read_lists::<i64>(DAMAGED, ReadPolicy { lone: LoneValue::Left, ..policy }).unwrap();
+        assert_eq!(lists.left, vec![3, 4, 2, 5, 1]);
+        assert_eq!(lists.right, vec![4, 0, -1, -1, 3]);
+
+        let lists = read_lists::<i64>("x\n", policy).unwrap();
+        assert_eq!((lists.left, lists.right), (vec![0], vec![-1]));
+    }
+
+    #[test]
+    fn compares_every_pair_of_columns() {
+        let columns = read_columns::<i64>("3   4   3\n4   3   4\n2   5   2\n1   3   1\n3   9   3\n3   3   3\n").unwrap();
+        let matrices = pairwise(&columns).unwrap();
+        assert_eq!(matrices.distance, vec![vec![0, 11, 0], vec![11, 0, 11], vec![0, 11, 0]]);
+        assert_eq!(matrices.similarity[0][1], 31);
+        assert_eq!(matrices.similarity[1][0], 31);
+        assert_eq!(matrices.similarity[0][0], 3 * 3 * 3 + 4 + 2 + 1);
+        assert_eq!(
+            matrices.to_json(),
+            "{\"columns\": 3, \"distance\": [[0, 11, 0], [11, 0, 11], [0, 11, 0]], \"similarity\": [[34, 31, 34], [31, 45, 31], [34, 31, 34]]}"
+        );
+    }
+
+    #[test]
+    fn prints_matrix_tables() {
+        let matrices = pairwise(&[vec![3, 4], vec![4, 3]]).unwrap();
+        assert_eq!(
+            matrices.table(),
+            "\
+distance     col 1     col 2
+col 1            0         0
+col 2            0         0
+
+similarity       col 1       col 2
+col 1                7           7
+col 2                7           7"
+        );
+        assert!(read_columns::<i64>("1 2 3\n4 5\n").is_err());
+        let err = read_columns::<i64>("\n  \n").unwrap_err();
+        assert_eq!(err.to_string(), "3:1: expected a row of integers, found end of input");
+    }
+
+    #[test]
+    fn reports_malformed_line() {
+        let err = read_file::<i32>("3   4\n4   x\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+    }
+}
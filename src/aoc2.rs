@@ -1,23 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-fn read_reports(file_path: &String) -> Vec<Vec<i32>> {
-    let path = Path::new(file_path);
-    let mut reports: Vec<Vec<i32>> = Vec::new();
-
-    let file = File::open(file_path).expect("Couldn't open file");
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        let line = line.expect("Couldn't read line");
-        let row: Vec<i32> = line
-            .split_whitespace()
-            .filter_map(|x| x.parse::<i32>().ok())
-            .collect();
-        reports.push(row);
-    }
+use crate::error::{Overflow, ParseError};
+use crate::parse::integer_rows;
+use crate::solution::{Answer, Solution};
 
-    reports
+pub fn read_reports(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    integer_rows(input)
 }
 
 fn check_monotonicity_decreasing(report: &[i32]) -> bool {
@@ -36,21 +22,21 @@ fn check_monotonicity_increasing(report: &[i32]) -> bool {
         )
 }
 
-fn check_monotonicity(report: &[i32]) -> bool {
+pub fn check_monotonicity(report: &[i32]) -> bool {
     check_monotonicity_decreasing(report) || check_monotonicity_increasing(report)
 }
 
-fn calculate_safe_reports(reports: &[Vec<i32>]) -> i32 {
+pub fn calculate_safe_reports(reports: &[Vec<i32>]) -> i32 {
     let mut safe_reports: i32 = 0;
     reports.iter().for_each(|x| {
         if check_monotonicity(x) {
-            safe_reports = safe_reports + 1;
+            safe_reports += 1;
         }
     });
     safe_reports
 }
 
-fn calculate_safe_reports_with_dampening(reports: &[Vec<i32>]) -> i32 {
+pub fn calculate_safe_reports_with_dampening(reports: &[Vec<i32>]) -> i32 {
     let mut safe_reports: i32 = 0;
 
     for report in reports {
@@ -71,10 +57,52 @@ fn calculate_safe_reports_with_dampening(reports: &[Vec<i32>]) -> i32 {
     safe_reports
 }
 
-pub fn run() {
-    let reports = read_reports(&"data/aoc2.txt".to_string());
-    println!("Solution for Problem 2 : ");
-    println!("Part 1: {}", calculate_safe_reports(&reports));
-    println!("Part 2: {}", calculate_safe_reports_with_dampening(&reports));
-    println!("***************");
-}
\ No newline at end of file
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        read_reports(raw)
+    }
+
+    fn part_one(&self, reports: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(calculate_safe_reports(reports).into())
+    }
+
+    fn part_two(&self, reports: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(calculate_safe_reports_with_dampening(reports).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{assert_example, Part};
+
+    const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+    #[test]
+    fn parses_example() {
+        let reports = read_reports(EXAMPLE).unwrap();
+        assert_eq!(reports.len(), 6);
+        assert_eq!(reports[0], vec![7, 6, 4, 2, 1]);
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day2, EXAMPLE, Part::One, 2);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day2, EXAMPLE, Part::Two, 4);
+    }
+}
@@ -1,11 +1,8 @@
+use crate::error::{Overflow, ParseError};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
-use std::fs;
 
-fn read_file(file_path: &str) -> String {
-    fs::read_to_string(file_path).expect("Can't read file")
-}
-
-fn uncorrupt(expression: &str) -> i32 {
+pub fn uncorrupt(expression: &str) -> i32 {
     let regex: &str = r"mul\((\d+),(\d+)\)";
     let re = Regex::new(regex).expect("Invalid regexp");
 
@@ -18,7 +15,7 @@ fn uncorrupt(expression: &str) -> i32 {
         .sum()
 }
 
-fn uncorrupt_extended(sample: &str) -> i32 {
+pub fn uncorrupt_extended(sample: &str) -> i32 {
     let regex = r"(mul\([1-9][0-9]{0,2},[1-9][0-9]{0,2}\)|don't|\bdo\b|do)";
     let re = Regex::new(regex).expect("Invalid regex");
 
@@ -52,10 +49,41 @@ fn uncorrupt_extended(sample: &str) -> i32 {
     sol
 }
 
-pub fn run() {
-    let expression = read_file("data/aoc3.txt");
-    println!("Solution for Problem 3 : ");
-    println!("Part 1 : {}", uncorrupt(&expression));
-    println!("Part 2 : {}", uncorrupt_extended(&expression));
-    println!("***************");
-}
\ No newline at end of file
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        Ok(raw.to_string())
+    }
+
+    fn part_one(&self, expression: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(uncorrupt(expression).into())
+    }
+
+    fn part_two(&self, expression: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(uncorrupt_extended(expression).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{assert_example, Part};
+
+    const EXAMPLE_PART_ONE: &str =
+        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    const EXAMPLE_PART_TWO: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day3, EXAMPLE_PART_ONE, Part::One, 161);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day3, EXAMPLE_PART_TWO, Part::Two, 48);
+    }
+}
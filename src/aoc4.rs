@@ -1,95 +1,118 @@
-use std::fs::File;
-use std::io;
-use std::io::BufRead;
-use std::path::Path;
-
-fn read_word_map(file_name: &str) -> Vec<Vec<char>> {
-    let path = Path::new(file_name);
-    let file = File::open(&path).expect("File not found");
-    let lines = io::BufReader::new(file).lines();
-
-    let word_map = lines
-        .filter_map(Result::ok)
-        .map(|line| line.chars().collect())
-        .collect();
-
-    word_map
-}
+use crate::direction::Direction;
+use crate::error::{Overflow, ParseError};
+use crate::grid::Grid;
+use crate::solution::{Answer, Solution};
+
+pub type WordMap = Grid<char>;
 
-fn is_inbounds(x: i32, y: i32, word_map: &[Vec<char>]) -> bool {
-    0 <= x && x < word_map.len() as i32 && 0 <= y && y < word_map[0].len() as i32
+pub fn read_word_map(input: &str) -> Result<WordMap, ParseError> {
+    Grid::parse(input)
 }
 
-fn check_1(word_map: &[Vec<char>], x: usize, y: usize) -> i32 {
+fn check_1(word_map: &WordMap, x: usize, y: usize) -> i32 {
     let mut matches = 0;
-    if word_map[x][y] == 'X' {
-        for dx in [-1, 0, 1] {
-            for dy in [-1, 0, 1] {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-                if is_inbounds(x as i32 + dx * 3, y as i32 + dy * 3, word_map) {
-                    let pattern: String = (0..4).map(|k| {
-                        let nx = x as i32 + k * dx;
-                        let ny = y as i32 + k * dy;
-                        word_map[nx as usize][ny as usize]
-                    })
-                        .collect();
-
-                    if pattern == "XMAS" {
-                        matches += 1;
-                    }
-                }
+    if word_map[(x, y)] == 'X' {
+        for dir in Direction::ALL {
+            let pattern: String = word_map
+                .ray((x, y), dir.delta())
+                .take(4)
+                .map(|pos| word_map[pos])
+                .collect();
+
+            if pattern == "XMAS" {
+                matches += 1;
             }
         }
     }
     matches
 }
 
-
-fn search_xmas(word_map: &Vec<Vec<char>>) -> usize {
+pub fn search_xmas(word_map: &WordMap) -> usize {
     let mut matches: usize = 0;
-    for i in 0..word_map.len() {
-        for j in 0..word_map[0].len() {
-            let mat = check_1(&word_map, i, j);
-            if mat > 0 {
-                matches += mat as usize;
-            }
+    for (i, j) in word_map.positions() {
+        let mat = check_1(word_map, i, j);
+        if mat > 0 {
+            matches += mat as usize;
         }
     }
     matches
 }
 
-fn search_x_mas(word_map: &Vec<Vec<char>>) -> usize {
+pub fn search_x_mas(word_map: &WordMap) -> usize {
     let mut matches: usize = 0;
 
-    for x in 0..word_map.len() {
-        for y in 0..word_map[0].len() {
-            if word_map[x][y] != 'A' {
-                continue;
-            }
+    for (x, y) in word_map.positions() {
+        if word_map[(x, y)] != 'A' {
+            continue;
+        }
 
-            if is_inbounds(x as i32 + 1, y as i32 + 1, word_map) &&
-                is_inbounds(x as i32 + 1, y as i32 - 1, word_map) &&
-                is_inbounds(x as i32 - 1, y as i32 - 1, word_map) &&
-                is_inbounds(x as i32 - 1, y as i32 + 1, word_map) {
-                let diag1 = (word_map[x - 1][y - 1], word_map[x + 1][y + 1]);
-                let diag2 = (word_map[x + 1][y - 1], word_map[x - 1][y + 1]);
-                if matches!(diag1, ('M', 'S') | ('S', 'M')) &&
-                    matches!(diag2, ('M', 'S') | ('S', 'M')) {
-                    matches += 1;
-                }
+        let corner = |dir: Direction| word_map.step((x, y), dir.delta()).map(|pos| word_map[pos]);
+        if let (Some(top_left), Some(bottom_right), Some(bottom_left), Some(top_right)) = (
+            corner(Direction::NorthWest),
+            corner(Direction::SouthEast),
+            corner(Direction::SouthWest),
+            corner(Direction::NorthEast),
+        ) {
+            let diag1 = (top_left, bottom_right);
+            let diag2 = (bottom_left, top_right);
+            if matches!(diag1, ('M', 'S') | ('S', 'M')) &&
+                matches!(diag2, ('M', 'S') | ('S', 'M')) {
+                matches += 1;
             }
         }
     }
     matches
 }
 
-pub fn run() {
-    println!("Solution for Problem 4 : ");
-    let word_map = read_word_map("data/aoc4.txt");
-    let matches = search_xmas(&word_map);
-    println!("Part 1: {}", matches);
-    println!("Part 2: {}", search_x_mas(&word_map));
-    println!("***************");
-}
\ No newline at end of file
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = WordMap;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        read_word_map(raw)
+    }
+
+    fn part_one(&self, word_map: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(search_xmas(word_map).into())
+    }
+
+    fn part_two(&self, word_map: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(search_x_mas(word_map).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{assert_example, Part};
+
+    const EXAMPLE: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
+    #[test]
+    fn parses_example() {
+        let word_map = read_word_map(EXAMPLE).unwrap();
+        assert_eq!((word_map.height(), word_map.width()), (10, 10));
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day4, EXAMPLE, Part::One, 18);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day4, EXAMPLE, Part::Two, 9);
+    }
+}
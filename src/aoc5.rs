@@ -1,50 +1,48 @@
+use crate::error::{Overflow, ParseError};
+use crate::parse::{list, pair, sections};
+use crate::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::hash::Hash;
-use std::io::{BufRead, BufReader};
 
-fn read_input(filename: &str) -> (Vec<i32>, Vec<i32>, Vec<Vec<i32>>) {
+pub type Input = (Vec<i32>, Vec<i32>, Vec<Vec<i32>>);
+
+pub fn read_input(input: &str) -> Result<Input, ParseError> {
     let mut first_list: Vec<i32> = Vec::new();
     let mut second_list: Vec<i32> = Vec::new();
     let mut print_lists: Vec<Vec<i32>> = Vec::new();
 
-    let file = File::open(filename).expect("Couldn't read given file!");
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        let line = line.expect("Couldn't read line");
-        if let Some(index) = line.find('|') {
-            let first = line[..index].trim().parse::<i32>().unwrap();
-            let second = line[index + 1..].trim().parse::<i32>().unwrap();
-            first_list.push(first);
-            second_list.push(second);
-        } else if line.contains(',') {
-            let list: Vec<i32> = line
-                .split(',')
-                .map(|x| x.parse::<i32>().unwrap())
-                .collect();
-            print_lists.push(list);
-        }
+    let sections = sections(input);
+    if let Some(&(line_no, line)) = sections.get(2).and_then(|extra| extra.first()) {
+        return Err(ParseError::at(line_no, line, line, "end of input"));
+    }
+    let mut sections = sections.into_iter();
+
+    for line in sections.next().unwrap_or_default() {
+        let (first, second) = pair(line, '|')?;
+        first_list.push(first);
+        second_list.push(second);
+    }
+    for line in sections.next().unwrap_or_default() {
+        print_lists.push(list(line, ',')?);
     }
 
-    (first_list, second_list, print_lists)
+    Ok((first_list, second_list, print_lists))
 }
 
-fn create_priority_map(first_list: &[i32], second_list: &[i32]) -> HashMap<i32, HashSet<i32>> {
+pub fn create_priority_map(first_list: &[i32], second_list: &[i32]) -> HashMap<i32, HashSet<i32>> {
     let mut priority_map: HashMap<i32, HashSet<i32>> = HashMap::new();
     for (&f, &s) in first_list.iter().zip(second_list.iter()) {
-        priority_map.entry(f).or_insert_with(HashSet::new).insert(s);
+        priority_map.entry(f).or_default().insert(s);
     }
     priority_map
 }
 
-fn check_list(print_list: &[i32], priority_map: &HashMap<i32, HashSet<i32>>) -> bool {
+pub fn check_list(print_list: &[i32], priority_map: &HashMap<i32, HashSet<i32>>) -> bool {
     let mut to_be_printed = Vec::new();
     for &elem in print_list {
         for &tbp in &to_be_printed {
             if priority_map
                 .get(&elem)
-                .map_or(false, |deps| deps.contains(&tbp))
+                .is_some_and(|deps| deps.contains(&tbp))
             {
                 return false;
             }
@@ -54,7 +52,7 @@ fn check_list(print_list: &[i32], priority_map: &HashMap<i32, HashSet<i32>>) ->
     true
 }
 
-fn check_valid_lists_and_find_mid_sum(
+pub fn check_valid_lists_and_find_mid_sum(
     print_lists: &[Vec<i32>], priority_map: &HashMap<i32, HashSet<i32>>) -> i32 {
     let mut sum = 0;
     for print_list in print_lists {
@@ -65,7 +63,7 @@ fn check_valid_lists_and_find_mid_sum(
     sum
 }
 
-fn find_invalid_lists(
+pub fn find_invalid_lists(
     print_lists: &[Vec<i32>],
     priority_map: &HashMap<i32, HashSet<i32>>,
 ) -> Vec<Vec<i32>> {
@@ -76,25 +74,17 @@ fn find_invalid_lists(
         .collect()
 }
 
-fn create_flip_lists(first_list: &[i32], second_list: &[i32]) -> HashMap<i32, HashSet<i32>> {
-    let mut priority_map_flip: HashMap<i32, HashSet<i32>> = HashMap::new();
-    for (&f, &s) in first_list.iter().zip(second_list.iter()) {
-        priority_map_flip.entry(s).or_insert_with(HashSet::new).insert(f);
-    }
-    priority_map_flip
-}
-
 fn custom_sort(x: &i32, y: &i32, priority_map: &HashMap<i32, HashSet<i32>>) -> std::cmp::Ordering {
-    if priority_map.get(x).map_or(false, |deps| deps.contains(y)) {
+    if priority_map.get(x).is_some_and(|deps| deps.contains(y)) {
         std::cmp::Ordering::Greater
-    } else if priority_map.get(y).map_or(false, |deps| deps.contains(x)) {
+    } else if priority_map.get(y).is_some_and(|deps| deps.contains(x)) {
         std::cmp::Ordering::Less
     } else {
         std::cmp::Ordering::Equal
     }
 }
 
-fn sort_incorrect_lists(
+pub fn sort_incorrect_lists(
     invalid_lists: &[Vec<i32>],
     priority_map: &HashMap<i32, HashSet<i32>>,
 ) -> Vec<Vec<i32>> {
@@ -108,25 +98,107 @@ fn sort_incorrect_lists(
         .collect()
 }
 
-fn find_mid_sum(incorrect_lists: &[Vec<i32>]) -> i32 {
+pub fn find_mid_sum(incorrect_lists: &[Vec<i32>]) -> i32 {
     incorrect_lists.iter().map(|list| list[list.len() / 2]).sum()
 }
 
-pub fn run() {
-    let (first_list, second_list, print_lists)
-        = read_input("data/aoc5.txt");
-    let priority_map = create_priority_map(&first_list, &second_list);
+pub struct Day5;
 
-    println!("Solution for Problem 5 : ");
+impl Solution for Day5 {
+    type Input = Input;
 
-    let sum = check_valid_lists_and_find_mid_sum(&print_lists, &priority_map);
-    println!("Part 1: {}", sum);
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        read_input(raw)
+    }
 
-    let invalid_lists = find_invalid_lists(&print_lists, &priority_map);
-    let flip_map = create_flip_lists(&first_list, &second_list);
-    let sorted_lists = sort_incorrect_lists(&invalid_lists, &priority_map);
+    fn part_one(&self, (first_list, second_list, print_lists): &Self::Input) -> Result<Answer, Overflow> {
+        let priority_map = create_priority_map(first_list, second_list);
+        Ok(check_valid_lists_and_find_mid_sum(print_lists, &priority_map).into())
+    }
 
-    println!("Part 2: {}", find_mid_sum(&sorted_lists));
+    fn part_two(&self, (first_list, second_list, print_lists): &Self::Input) -> Result<Answer, Overflow> {
+        let priority_map = create_priority_map(first_list, second_list);
+        let invalid_lists = find_invalid_lists(print_lists, &priority_map);
+        let sorted_lists = sort_incorrect_lists(&invalid_lists, &priority_map);
+        Ok(find_mid_sum(&sorted_lists).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{assert_example, Part};
+
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn parses_example() {
+        let (first_list, second_list, print_lists) = read_input(EXAMPLE).unwrap();
+        assert_eq!((first_list.len(), second_list.len()), (21, 21));
+        assert_eq!((first_list[0], second_list[0]), (47, 53));
+        assert_eq!(print_lists.len(), 6);
+        assert_eq!(print_lists[2], vec![75, 29, 13]);
+    }
 
-    println!("***************");
-}
\ No newline at end of file
+    #[test]
+    fn rejects_a_third_section() {
+        let err = read_input("47|53\n\n75,47\n\n1,2\n").unwrap_err();
+        assert_eq!((err.line, err.column), (5, 1));
+    }
+
+    // `custom_sort` puts pages back to front, which leaves the middle page,
+    // and so the answer, unchanged.
+    #[test]
+    fn custom_sort_orders_invalid_updates_back_to_front() {
+        let (first_list, second_list, print_lists) = read_input(EXAMPLE).unwrap();
+        let priority_map = create_priority_map(&first_list, &second_list);
+        let invalid_lists = find_invalid_lists(&print_lists, &priority_map);
+        assert_eq!(
+            sort_incorrect_lists(&invalid_lists, &priority_map),
+            vec![
+                vec![53, 61, 47, 75, 97],
+                vec![13, 29, 61],
+                vec![13, 29, 47, 75, 97],
+            ]
+        );
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day5, EXAMPLE, Part::One, 143);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day5, EXAMPLE, Part::Two, 123);
+    }
+}
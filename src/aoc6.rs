@@ -1,226 +1,225 @@
-use once_cell::sync::Lazy;
+use crate::cancel;
+use crate::direction::Direction;
+use crate::error::{Overflow, ParseError};
+use crate::grid::Grid;
+use crate::solution::{Answer, Part, Solution, Variant};
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::time::Instant;
-
-static MOVE_DIR: Lazy<HashMap<char, (isize, isize)>> = Lazy::new(|| {
-    vec![
-        ('>', (0, 1)),
-        ('v', (1, 0)),
-        ('<', (0, -1)),
-        ('^', (-1, 0)),
-    ]
-        .into_iter()
-        .collect()
-});
-static ROTATE_DIR: Lazy<HashMap<char, char>> = Lazy::new(|| {
-    vec![
-        ('>', 'v'),
-        ('v', '<'),
-        ('<', '^'),
-        ('^', '>'),
-    ]
-        .into_iter()
-        .collect()
-});
-type Map = Vec<Vec<char>>;
-
-fn read_map(filepath: &str) -> Map {
-    fs::read_to_string(filepath)
-        .expect("Unable to read file")
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
+use std::collections::HashSet;
 
-fn determine_start_position(map: &Map) -> (usize, usize) {
-    for (i, row) in map.iter().enumerate() {
-        for (j, &ch) in row.iter().enumerate() {
-            if ['>', 'v', '<', '^'].contains(&ch) {
-                return (i, j);
-            }
+pub type Map = Grid<char>;
+
+pub fn read_map(input: &str) -> Result<Map, ParseError> {
+    let map = Grid::parse(input)?;
+
+    let mut guard_seen = false;
+    for (x, y) in map.positions() {
+        let ch = map[(x, y)];
+        let is_guard = Direction::from_arrow(ch).is_some();
+        if !is_guard && ch != '.' && ch != '#' {
+            return Err(ParseError::new(x + 1, y + 1, "one of `.#^>v<`", format!("`{}`", ch)));
         }
+        if is_guard && guard_seen {
+            return Err(ParseError::new(x + 1, y + 1, "a single guard", "a second guard"));
+        }
+        guard_seen |= is_guard;
     }
-    panic!("Unable to find start position");
+
+    Ok(map)
 }
 
-fn is_valid_position(map: &Map, nx: isize, ny: isize) -> bool {
-    if nx < 0
-        || ny < 0
-        || nx as usize >= map.len()
-        || ny as usize >= map[0].len()
-        || map[nx as usize][ny as usize] == '#'
-        || map[nx as usize][ny as usize] == 'O'
-    {
-        return false;
-    }
-    true
+pub fn determine_start_position(map: &Map) -> Option<(usize, usize)> {
+    map.position(|&ch| Direction::from_arrow(ch).is_some())
 }
 
-fn trace_map(map: &Map, mut x: usize, mut y: usize) -> usize {
-    let mut visited = vec![vec![false; map[0].len()]; map.len()];
-    let mut unique_paths = 0;
-    let mut current_dir = map[x][y];
+fn start_direction(map: &Map, x: usize, y: usize) -> Direction {
+    Direction::from_arrow(map[(x, y)]).expect("the guard should start on an arrow")
+}
 
-    loop {
-        while let Some(&(dx, dy)) = MOVE_DIR.get(&current_dir) {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
+/// The next position from `(x, y)` facing `dir`, unless it is off the map or
+/// blocked.
+fn next_valid_position(map: &Map, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+    map.step((x, y), dir.delta())
+        .filter(|&pos| map[pos] != '#' && map[pos] != 'O')
+}
 
-            if !is_valid_position(&map, nx, ny)
-            {
-                break;
-            }
+fn is_leaving_map(map: &Map, x: usize, y: usize, dir: Direction) -> bool {
+    map.step((x, y), dir.delta()).is_none()
+}
+
+pub fn trace_map(map: &Map, mut x: usize, mut y: usize) -> usize {
+    let mut visited = Grid::filled(map.width(), map.height(), false);
+    let mut unique_paths = 0;
+    let mut current_dir = start_direction(map, x, y);
 
-            x = nx as usize;
-            y = ny as usize;
+    loop {
+        while let Some(next) = next_valid_position(map, x, y, current_dir) {
+            (x, y) = next;
 
-            if !visited[x][y] {
-                visited[x][y] = true;
+            if !visited[(x, y)] {
+                visited[(x, y)] = true;
                 unique_paths += 1;
             }
         }
 
-        if let Some(&(dx, dy)) = MOVE_DIR.get(&current_dir) {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-
-            if nx < 0 || ny < 0 || nx as usize >= map.len() || ny as usize >= map[0].len() {
-                if !visited[x][y] {
-                    visited[x][y] = true;
-                    unique_paths += 1;
-                }
-                break;
+        if is_leaving_map(map, x, y, current_dir) {
+            if !visited[(x, y)] {
+                visited[(x, y)] = true;
+                unique_paths += 1;
             }
-        }
-
-        if let Some(&next_dir) = ROTATE_DIR.get(&current_dir) {
-            current_dir = next_dir;
-        } else {
             break;
         }
+
+        current_dir = current_dir.turn_right();
     }
     unique_paths
 }
 
-fn trace_map_and_get_positions(map: &Map, mut x: usize, mut y: usize) -> Vec<(usize, usize)> {
-    let mut visited = vec![vec![false; map[0].len()]; map.len()];
+pub fn trace_map_and_get_positions(map: &Map, mut x: usize, mut y: usize) -> Vec<(usize, usize)> {
+    let mut visited = Grid::filled(map.width(), map.height(), false);
     let mut positions = Vec::new();
-    let mut current_dir = map[x][y];
+    let mut current_dir = start_direction(map, x, y);
 
     loop {
-        while let Some(&(dx, dy)) = MOVE_DIR.get(&current_dir) {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
+        while let Some(next) = next_valid_position(map, x, y, current_dir) {
+            (x, y) = next;
 
-            if !is_valid_position(&map, nx, ny)
-            {
-                break;
-            }
-
-            x = nx as usize;
-            y = ny as usize;
-
-            if !visited[x][y] {
-                visited[x][y] = true;
+            if !visited[(x, y)] {
+                visited[(x, y)] = true;
                 positions.push((x, y));
             }
         }
 
-        if let Some(&next_dir) = ROTATE_DIR.get(&current_dir) {
-            current_dir = next_dir;
-        } else {
-            break;
-        }
-        if x == 0 || y == 0 || x == map.len() - 1 || y == map[0].len() - 1 {
+        current_dir = current_dir.turn_right();
+        if x == 0 || y == 0 || x == map.height() - 1 || y == map.width() - 1 {
             break;
         }
     }
     positions
 }
 
-fn trace_map_and_detect_cycles(map: &Map, mut x: usize, mut y: usize) -> bool {
-    let mut visited = vec![vec![(false, HashSet::new()); map[0].len()]; map.len()];
-    let mut current_dir = map[x][y];
+pub fn trace_map_and_detect_cycles(map: &Map, mut x: usize, mut y: usize) -> bool {
+    let mut visited: Grid<HashSet<Direction>> = Grid::filled(map.width(), map.height(), HashSet::new());
+    let mut current_dir = start_direction(map, x, y);
 
     loop {
-        while let Some(&(dx, dy)) = MOVE_DIR.get(&current_dir) {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-
-            if !is_valid_position(map, nx, ny) || map[nx as usize][ny as usize] == 'O' {
-                break;
-            }
-
-            x = nx as usize;
-            y = ny as usize;
+        while let Some(next) = next_valid_position(map, x, y, current_dir) {
+            (x, y) = next;
 
-            if visited[x][y].0 && visited[x][y].1.contains(&current_dir) {
+            if !visited[(x, y)].insert(current_dir) {
                 return true;
-            } else {
-                visited[x][y].0 = true;
-                visited[x][y].1.insert(current_dir);
             }
         }
 
-        if let Some(&(dx, dy)) = MOVE_DIR.get(&current_dir) {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-
-            if nx < 0 || ny < 0 || nx as usize >= map.len() || ny as usize >= map[0].len() {
-                return false;
-            }
+        if is_leaving_map(map, x, y, current_dir) || cancel::expired() {
+            return false;
         }
 
-        if let Some(&next_dir) = ROTATE_DIR.get(&current_dir) {
-            current_dir = next_dir;
-        } else {
-            break;
-        }
+        current_dir = current_dir.turn_right();
     }
-
-    false
 }
 
 fn process_obstacle(map: &Map, x: usize, y: usize, obstacle: (usize, usize)) -> bool {
+    // The guard is standing there, so it cannot hold an obstacle.
+    if obstacle == (x, y) {
+        return false;
+    }
     let mut mod_map = map.clone();
-    mod_map[obstacle.0][obstacle.1] = 'O';
+    mod_map[obstacle] = 'O';
     trace_map_and_detect_cycles(&mod_map, x, y)
 }
 
-pub fn run() {
-    let map: Map = read_map("data/aoc6.txt");
-    let (x, y) = determine_start_position(&map);
+pub fn count_loop_obstacles_serial(map: &Map, (x, y): (usize, usize)) -> usize {
+    trace_map_and_get_positions(map, x, y)
+        .iter()
+        .filter(|&&obstacle| !cancel::expired() && process_obstacle(map, x, y, obstacle))
+        .count()
+}
 
-    println!("Solution for Problem 6 : ");
+pub fn count_loop_obstacles_parallel(map: &Map, (x, y): (usize, usize)) -> usize {
+    let deadline = cancel::deadline();
+    trace_map_and_get_positions(map, x, y)
+        .par_iter()
+        .filter(|&&obstacle| {
+            cancel::with_deadline(deadline, || !cancel::expired() && process_obstacle(map, x, y, obstacle))
+        })
+        .count()
+}
 
-    let path_covered = trace_map(&map, x, y);
-    println!("Part 1: {}", path_covered);
+pub struct Day6;
 
-    let positions = trace_map_and_get_positions(&map, x, y);
-    let potential_obstacles = &positions;
+impl Solution for Day6 {
+    type Input = (Map, (usize, usize));
 
-    let start = Instant::now();
-    let non_parallel_result: usize = potential_obstacles
-        .iter()
-        .filter(|&&obstacle| process_obstacle(&map, x, y, obstacle))
-        .count();
-    println!(
-        "Part 2 Non-parallel: {}, time : {:?}",
-        non_parallel_result,
-        start.elapsed()
-    );
-
-    let start = Instant::now();
-    let parallel_result: usize = potential_obstacles
-        .par_iter()
-        .filter(|&&obstacle| process_obstacle(&map, x, y, obstacle))
-        .count();
-    println!(
-        "Part 2 Parallel: {}, time : {:?}",
-        parallel_result,
-        start.elapsed()
-    );
-    println!("***************");
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        let map = read_map(raw)?;
+        let start = determine_start_position(&map).ok_or_else(|| {
+            ParseError::new(map.height() + 1, 1, "a guard (`^`, `>`, `v` or `<`)", "end of input")
+        })?;
+        Ok((map, start))
+    }
+
+    fn part_one(&self, (map, (x, y)): &Self::Input) -> Result<Answer, Overflow> {
+        Ok(trace_map(map, *x, *y).into())
+    }
+
+    fn part_two(&self, (map, start): &Self::Input) -> Result<Answer, Overflow> {
+        Ok(count_loop_obstacles_parallel(map, *start).into())
+    }
+
+    fn variants(&self) -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant {
+                part: Part::Two,
+                name: "serial",
+                solve: |(map, start)| Ok(count_loop_obstacles_serial(map, *start).into()),
+            },
+            Variant {
+                part: Part::Two,
+                name: "parallel",
+                solve: |(map, start)| Ok(count_loop_obstacles_parallel(map, *start).into()),
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::assert_example;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    #[test]
+    fn parses_example() {
+        let (map, start) = Day6.parse(EXAMPLE).unwrap();
+        assert_eq!((map.height(), map.width()), (10, 10));
+        assert_eq!(start, (6, 4));
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day6, EXAMPLE, Part::One, 41);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day6, EXAMPLE, Part::Two, 6);
+    }
+
+    #[test]
+    fn rejects_map_without_guard() {
+        let err = Day6.parse("..\n.#\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
 }
@@ -1,29 +1,17 @@
+use crate::cancel;
+use crate::error::{Overflow, ParseError};
+use crate::parse::{key_values, non_blank_lines};
+use crate::solution::{Answer, Part, Solution, Variant};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io;
-use std::io::BufRead;
-use std::time::Instant;
 
-type Equation = (i64, Vec<i64>);
+pub type Equation = (i64, Vec<i64>);
 
-fn read_equations(filepath: &str) -> Vec<Equation> {
-    let mut equations: Vec<Equation> = Vec::new();
-    let file = File::open(filepath).expect("File not found");
-    let reader = io::BufReader::new(file);
-
-    for line in reader.lines() {
-        let line = line.expect("Problem reading file");
-        let mut parts = line.split_whitespace();
-        let val: i64 = parts.next().unwrap().trim_end_matches(':').parse().unwrap();
-        let equation: Vec<i64> = parts.map(|x| x.parse().unwrap()).collect();
-        equations.push((val, equation));
-    }
-
-    equations
+pub fn read_equations(input: &str) -> Result<Vec<Equation>, ParseError> {
+    non_blank_lines(input).map(key_values).collect()
 }
 
 fn place_operations_helper_recursion(equations: &[i64], curr_ind: usize, sol: i64, sum: i64) -> bool {
-    if sum > sol {
+    if sum > sol || cancel::expired() {
         return false;
     }
 
@@ -71,7 +59,7 @@ fn place_operations_helper_dp_top_down(equations: &[i64], curr_ind: usize, sol:
     result
 }
 
-fn place_operations_helper_dp_bottom_up(equations: &[i64], sol: i64) -> bool {
+pub fn place_operations_helper_dp_bottom_up(equations: &[i64], sol: i64) -> bool {
     if equations.is_empty() {
         return false;
     }
@@ -91,40 +79,8 @@ fn place_operations_helper_dp_bottom_up(equations: &[i64], sol: i64) -> bool {
     current.contains(&sol)
 }
 
-fn run_first_part(filepath: &str) {
-    let all_equations = read_equations(filepath);
-    let mut accum_val = 0;
-
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        if place_operations_helper_recursion(equations, 1, *val, equations[0]) {
-            accum_val += val;
-        }
-    }
-    println!("Part 1 took {:?} with naive recursion based approach, solution : {}", start.elapsed(), accum_val);
-
-    accum_val = 0;
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        let mut dp_top_down_memo: HashMap<(usize, i64), bool> = HashMap::new();
-        if place_operations_helper_dp_top_down(equations, 1, *val, equations[0], &mut dp_top_down_memo) {
-            accum_val += val;
-        }
-    }
-    println!("Part 1 took {:?} with top down Dynamic Programming based approach, solution : {}", start.elapsed(), accum_val);
-
-    accum_val = 0;
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        if place_operations_helper_dp_bottom_up(equations, *val) {
-            accum_val += val;
-        }
-    }
-    println!("Part 1 took {:?} with bottom up Dynamic Programming based approach, solution : {}", start.elapsed(), accum_val);
-}
-
 fn place_operations_extended_helper_recursion(equations: &[i64], curr_ind: usize, sol: i64, sum: i64) -> bool {
-    if sum > sol {
+    if sum > sol || cancel::expired() {
         return false;
     }
 
@@ -162,7 +118,7 @@ fn place_operations_extended_helper_dp_top_down(equations: &[i64], curr_ind: usi
     result
 }
 
-fn place_operations_extended_helper_dp_bottom_up(equations: &[i64], sol: i64) -> bool {
+pub fn place_operations_extended_helper_dp_bottom_up(equations: &[i64], sol: i64) -> bool {
     if equations.is_empty() {
         return false;
     }
@@ -182,41 +138,132 @@ fn place_operations_extended_helper_dp_bottom_up(equations: &[i64], sol: i64) ->
     current.contains(&sol)
 }
 
-fn run_second_part(filepath: &str) {
-    let all_equations = read_equations(filepath);
-    let mut accum_val = 0;
+pub type Solvable = fn(&[i64], i64) -> bool;
 
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        if place_operations_extended_helper_recursion(equations, 1, *val, equations[0]) {
-            accum_val += val;
-        }
+fn solvable_recursion(equations: &[i64], sol: i64) -> bool {
+    place_operations_helper_recursion(equations, 1, sol, equations[0])
+}
+
+fn solvable_dp_top_down(equations: &[i64], sol: i64) -> bool {
+    let mut dp_top_down_memo: HashMap<(usize, i64), bool> = HashMap::new();
+    place_operations_helper_dp_top_down(equations, 1, sol, equations[0], &mut dp_top_down_memo)
+}
+
+fn solvable_extended_recursion(equations: &[i64], sol: i64) -> bool {
+    place_operations_extended_helper_recursion(equations, 1, sol, equations[0])
+}
+
+fn solvable_extended_dp_top_down(equations: &[i64], sol: i64) -> bool {
+    let mut dp_top_down_memo: HashMap<(usize, i64), bool> = HashMap::new();
+    place_operations_extended_helper_dp_top_down(equations, 1, sol, equations[0], &mut dp_top_down_memo)
+}
+
+pub fn calibration_total(all_equations: &[Equation], solvable: Solvable) -> i64 {
+    all_equations
+        .iter()
+        .filter(|(val, equations)| solvable(equations, *val))
+        .map(|(val, _)| val)
+        .sum()
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<Equation>;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {
+        read_equations(raw)
     }
-    println!("Part 2 took {:?} with naive recursion based approach, solution : {}", start.elapsed(), accum_val);
 
-    accum_val = 0;
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        let mut dp_top_down_memo: HashMap<(usize, i64), bool> = HashMap::new();
-        if place_operations_extended_helper_dp_top_down(equations, 1, *val, equations[0], &mut dp_top_down_memo) {
-            accum_val += val;
-        }
+    fn part_one(&self, all_equations: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(calibration_total(all_equations, place_operations_helper_dp_bottom_up).into())
     }
-    println!("Part 2 took {:?} with top down Dynamic Programming based approach, solution : {}", start.elapsed(), accum_val);
 
-    accum_val = 0;
-    let start = Instant::now();
-    for (val, equations) in &all_equations {
-        if place_operations_extended_helper_dp_bottom_up(equations, *val) {
-            accum_val += val;
-        }
+    fn part_two(&self, all_equations: &Self::Input) -> Result<Answer, Overflow> {
+        Ok(calibration_total(all_equations, place_operations_extended_helper_dp_bottom_up).into())
+    }
+
+    fn variants(&self) -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant {
+                part: Part::One,
+                name: "recursion",
+                solve: |all_equations| Ok(calibration_total(all_equations, solvable_recursion).into()),
+            },
+            Variant {
+                part: Part::One,
+                name: "top-down",
+                solve: |all_equations| Ok(calibration_total(all_equations, solvable_dp_top_down).into()),
+            },
+            Variant {
+                part: Part::One,
+                name: "bottom-up",
+                solve: |all_equations| {
+                    Ok(calibration_total(all_equations, place_operations_helper_dp_bottom_up).into())
+                },
+            },
+            Variant {
+                part: Part::Two,
+                name: "recursion",
+                solve: |all_equations| {
+                    Ok(calibration_total(all_equations, solvable_extended_recursion).into())
+                },
+            },
+            Variant {
+                part: Part::Two,
+                name: "top-down",
+                solve: |all_equations| {
+                    Ok(calibration_total(all_equations, solvable_extended_dp_top_down).into())
+                },
+            },
+            Variant {
+                part: Part::Two,
+                name: "bottom-up",
+                solve: |all_equations| {
+                    Ok(calibration_total(all_equations, place_operations_extended_helper_dp_bottom_up).into())
+                },
+            },
+        ]
     }
-    println!("Part 2 took {:?} with bottom up Dynamic Programming based approach, solution : {}", start.elapsed(), accum_val);
 }
 
-pub fn run() {
-    println!("Solution for Problem 7 : ");
-    run_first_part("data/aoc7.txt");
-    run_second_part("data/aoc7.txt");
-    println!("***************");
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::assert_example;
+
+    const EXAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+";
+
+    #[test]
+    fn parses_example() {
+        let equations = read_equations(EXAMPLE).unwrap();
+        assert_eq!(equations.len(), 9);
+        assert_eq!(equations[1], (3267, vec![81, 40, 27]));
+    }
+
+    #[test]
+    fn part_one_example() {
+        assert_example(&Day7, EXAMPLE, Part::One, 3749);
+    }
+
+    #[test]
+    fn part_two_example() {
+        assert_example(&Day7, EXAMPLE, Part::Two, 11387);
+    }
+
+    #[test]
+    fn reports_missing_colon() {
+        let err = read_equations("190 10 19\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 10));
+    }
+}
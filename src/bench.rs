@@ -0,0 +1,178 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How many times a step is run before and while it is measured.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+impl BenchConfig {
+    /// A single measured run, for timing answers that are computed anyway.
+    pub fn once() -> Self {
+        BenchConfig { warmup: 0, runs: 1 }
+    }
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { warmup: 1, runs: 10 }
+    }
+}
+
+/// Summary of repeated timings of one step.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        assert!(!samples.is_empty(), "Need at least one sample");
+        samples.sort();
+
+        let runs = samples.len();
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+        let mean_secs = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            samples
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean_secs).powi(2))
+                .sum::<f64>()
+                / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            runs,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Runs `step` `config.warmup` times unmeasured, then `config.runs` times
+/// measured, returning the last result along with the timings.
+pub fn measure<T>(config: &BenchConfig, mut step: impl FnMut() -> T) -> (T, Stats) {
+    for _ in 0..config.warmup {
+        black_box(step());
+    }
+
+    let mut samples = Vec::with_capacity(config.runs.max(1));
+    let mut result = None;
+    for _ in 0..config.runs.max(1) {
+        let start = Instant::now();
+        let value = black_box(step());
+        samples.push(start.elapsed());
+        result = Some(value);
+    }
+
+    (result.expect("At least one measured run"), Stats::from_samples(samples))
+}
+
+/// One row of benchmark output.
+pub struct Measurement {
+    pub day: u8,
+    pub step: String,
+    pub stats: Stats,
+    /// The time limit, if the step ran past it and its timings mean nothing.
+    pub timeout: Option<Duration>,
+}
+
+pub fn print_table(measurements: &[Measurement]) {
+    println!(
+        "{:>3}  {:<40} {:>5} {:>12} {:>12} {:>12} {:>12}",
+        "day", "step", "runs", "min", "median", "mean", "std dev"
+    );
+    for m in measurements {
+        match m.timeout {
+            Some(limit) => println!("{:>3}  {:<40} TIMEOUT (limit {:.2?})", m.day, m.step, limit),
+            None => println!(
+                "{:>3}  {:<40} {:>5} {:>12.2?} {:>12.2?} {:>12.2?} {:>12.2?}",
+                m.day, m.step, m.stats.runs, m.stats.min, m.stats.median, m.stats.mean, m.stats.std_dev
+            ),
+        }
+    }
+}
+
+pub fn print_json(measurements: &[Measurement]) {
+    let rows: Vec<String> = measurements
+        .iter()
+        .map(|m| {
+            format!(
+                "  {{\"day\": {}, \"step\": {}, \"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"std_dev_ns\": {}, \"timeout_ns\": {}}}",
+                m.day,
+                crate::json::string(&m.step),
+                m.stats.runs,
+                m.stats.min.as_nanos(),
+                m.stats.median.as_nanos(),
+                m.stats.mean.as_nanos(),
+                m.stats.std_dev.as_nanos(),
+                m.timeout.map_or("null".to_string(), |limit| limit.as_nanos().to_string())
+            )
+        })
+        .collect();
+    println!("[\n{}\n]", rows.join(",\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn assert_close(actual: Duration, expected_ms: f64) {
+        let actual_ms = actual.as_secs_f64() * 1000.0;
+        assert!((actual_ms - expected_ms).abs() < 1e-6, "{} ms, expected {} ms", actual_ms, expected_ms);
+    }
+
+    #[test]
+    fn summarises_odd_samples() {
+        let stats = Stats::from_samples(millis(&[3, 1, 2]));
+        assert_eq!(stats.runs, 3);
+        assert_eq!((stats.min, stats.median), (Duration::from_millis(1), Duration::from_millis(2)));
+        assert_close(stats.mean, 2.0);
+        assert_close(stats.std_dev, 1.0);
+    }
+
+    #[test]
+    fn summarises_even_samples() {
+        let stats = Stats::from_samples(millis(&[4, 1, 3, 2]));
+        assert_eq!(stats.runs, 4);
+        assert_eq!((stats.min, stats.median), (Duration::from_millis(1), Duration::from_micros(2500)));
+        assert_close(stats.mean, 2.5);
+        assert_close(stats.std_dev, (5.0f64 / 3.0).sqrt());
+    }
+
+    #[test]
+    fn one_sample_has_no_spread() {
+        let stats = Stats::from_samples(millis(&[7]));
+        assert_eq!((stats.min, stats.median), (Duration::from_millis(7), Duration::from_millis(7)));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+        assert_close(stats.mean, 7.0);
+    }
+
+    #[test]
+    fn measures_every_run() {
+        let mut calls = 0;
+        let (last, stats) = measure(&BenchConfig { warmup: 2, runs: 3 }, || {
+            calls += 1;
+            calls
+        });
+        assert_eq!((calls, last, stats.runs), (5, 5, 3));
+    }
+}
@@ -0,0 +1,149 @@
+//! Cooperative cancellation for long-running solvers.
+//!
+//! The runner calls a solver inside [`with_deadline`], and the slow loops of a
+//! solver poll [`expired`] and give up early once it returns true. The answer
+//! of a solver that gave up is meaningless, so the runner reports the part as
+//! timed out instead. Work handed to other threads, such as a rayon
+//! `par_iter`, has to pass the [`deadline`] along with [`with_deadline`].
+
+use crate::solution::Part;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// `expired` only reads the clock once every this many calls.
+const CHECK_EVERY: u32 = 64;
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static EXPIRED: Cell<bool> = const { Cell::new(false) };
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Runs `f` with `deadline` as the current thread's deadline, restoring the
+/// previous one afterwards.
+pub fn with_deadline<T>(deadline: Option<Instant>, f: impl FnOnce() -> T) -> T {
+    let previous = (DEADLINE.replace(deadline), EXPIRED.replace(false));
+    let result = f();
+    DEADLINE.set(previous.0);
+    EXPIRED.set(previous.1);
+    result
+}
+
+/// The current thread's deadline, if it has one.
+pub fn deadline() -> Option<Instant> {
+    DEADLINE.get()
+}
+
+/// Whether the current thread's deadline has passed. Cheap enough to call in
+/// a hot loop; once true it stays true until the deadline is replaced.
+pub fn expired() -> bool {
+    if EXPIRED.get() {
+        return true;
+    }
+    let Some(deadline) = DEADLINE.get() else {
+        return false;
+    };
+    let calls = CALLS.get().wrapping_add(1);
+    CALLS.set(calls);
+    if calls.is_multiple_of(CHECK_EVERY) && Instant::now() >= deadline {
+        EXPIRED.set(true);
+    }
+    EXPIRED.get()
+}
+
+/// Time limits for solving a part, from the most specific setting that applies:
+/// `DAY.PART=SECS`, then `DAY=SECS`, then a plain `SECS` for everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeouts {
+    default: Option<Duration>,
+    days: HashMap<u8, Duration>,
+    parts: HashMap<(u8, Part), Duration>,
+}
+
+fn parse_seconds(spec: &str) -> Result<Duration, String> {
+    spec.parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("`{}` is not a positive number of seconds", spec))
+}
+
+impl Timeouts {
+    pub fn limit(&self, day: u8, part: Part) -> Option<Duration> {
+        self.parts
+            .get(&(day, part))
+            .or_else(|| self.days.get(&day))
+            .or(self.default.as_ref())
+            .copied()
+    }
+
+    /// Adds one `SECS`, `DAY=SECS` or `DAY.PART=SECS` setting.
+    pub fn add(&mut self, spec: &str) -> Result<(), String> {
+        let Some((target, secs)) = spec.split_once('=') else {
+            self.default = Some(parse_seconds(spec)?);
+            return Ok(());
+        };
+        let limit = parse_seconds(secs)?;
+        let bad_target = || format!("`{}` is not a day or `DAY.PART`", target);
+        match target.split_once('.') {
+            None => {
+                let day = target.parse().map_err(|_| bad_target())?;
+                self.days.insert(day, limit);
+            }
+            Some((day, part)) => {
+                let day = day.parse().map_err(|_| bad_target())?;
+                let part = match part {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => return Err(bad_target()),
+                };
+                self.parts.insert((day, part), limit);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_after_the_deadline() {
+        assert!(!expired());
+        with_deadline(Some(Instant::now()), || {
+            assert!((0..CHECK_EVERY).any(|_| expired()));
+            assert!(expired());
+        });
+        assert!(!expired());
+
+        let far = Instant::now() + Duration::from_secs(3600);
+        with_deadline(Some(far), || {
+            assert_eq!(deadline(), Some(far));
+            assert!(!(0..10 * CHECK_EVERY).any(|_| expired()));
+        });
+        assert_eq!(deadline(), None);
+    }
+
+    #[test]
+    fn picks_the_most_specific_limit() {
+        let mut timeouts = Timeouts::default();
+        assert_eq!(timeouts.limit(6, Part::Two), None);
+        for spec in ["10", "6=5", "6.2=0.5"] {
+            timeouts.add(spec).unwrap();
+        }
+        assert_eq!(timeouts.limit(1, Part::One), Some(Duration::from_secs(10)));
+        assert_eq!(timeouts.limit(6, Part::One), Some(Duration::from_secs(5)));
+        assert_eq!(timeouts.limit(6, Part::Two), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn rejects_bad_limits() {
+        let mut timeouts = Timeouts::default();
+        assert!(timeouts.add("0").is_err());
+        assert!(timeouts.add("6=fast").is_err());
+        assert!(timeouts.add("6.3=1").is_err());
+        assert!(timeouts.add("x=1").is_err());
+    }
+}
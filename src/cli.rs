@@ -0,0 +1,534 @@
+use advent2024::aoc1::{LoneValue, Policy, ReadPolicy};
+use advent2024::external_sort::DEFAULT_CHUNK_LEN;
+use advent2024::memory;
+use advent2024::registry;
+use advent2024::runner::{Format, InputSource, Mode, Options};
+use advent2024::solution::Part;
+use advent2024::store::Store;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: AdventOfCode24_Rust [OPTIONS]
+       AdventOfCode24_Rust new-day <N> [--store <DIR>]
+       AdventOfCode24_Rust fetch <N> [--store <DIR>]
+       AdventOfCode24_Rust submit <N> <PART> <ANSWER> [--store <DIR>]
+       AdventOfCode24_Rust report [OPTIONS] [--redact] [--output <FILE>]
+       AdventOfCode24_Rust matrix <FILE> [--format <FMT>]
+       AdventOfCode24_Rust stream <FILE> [--chunk-len <N>] [--format <FMT>]
+
+Commands:
+  new-day <N>          Write src/aocN.rs from a template with an example test stub,
+                       create an empty aocN.txt in the input store and register the day
+  fetch <N>            Download the real input for day N into the input store,
+                       unless it is already there
+  submit <N> <PART> <ANSWER>
+                       Submit an answer and print the verdict; answers judged
+                       before are never sent again
+  report               Time the default solver and every variant of the selected
+                       days and parts and print a Markdown table of the answers,
+                       median times and speedups over the slowest variant.
+                       `--redact` hides the answers and `--output <FILE>` writes
+                       the table to FILE, between its `<!-- report:start -->` and
+                       `<!-- report:end -->` lines if FILE already exists
+  matrix <FILE>        Read any number of day 1 style columns from FILE, `-` for
+                       stdin, and print the distance and similarity of every pair
+                       of columns as tables, or as JSON with `--format json`
+  stream <FILE>        Solve both parts of day 1 for FILE, `-` for stdin, reading it
+                       line by line and sorting the columns in chunks of at most
+                       `--chunk-len <N>` values spilled to temporary files, for lists
+                       larger than memory [default chunk length: 1048576]
+
+Options:
+  -d, --day <DAYS>     Days to run: `all`, a single day `6`, a range `1-5`
+                       or a comma separated mix such as `1,3-5` [default: all]
+  -p, --part <PART>    Part to run: `1`, `2` or `both` [default: both]
+  -i, --input <FILE>   Input file for a single selected day, `-` reads stdin
+                       [default: the real input in the store]
+  -e, --example <NAME> Use the example input called NAME from the store instead
+                       of the real one
+      --store <DIR>    Input store holding aocN.txt, aocN/<example>.txt and
+                       answers.toml [default: $AOC_STORE, then `store` in the
+                       config file at $AOC_CONFIG or ~/.config/advent2024/config.toml,
+                       then data]
+      --variant <NAME> Solve the selected parts of a single day with a named variant
+      --compare        Also time every variant of the selected parts
+      --check-variants Run every variant of the selected parts and fail if any of
+                       them disagrees with the default solver
+      --verify         Check the answers against the known-good ones instead of
+                       printing them, reporting PASS, FAIL or MISSING
+      --answers <FILE> Known-good answers used by `--verify` [default: answers.toml
+                       in the store]
+      --parallel       Run the selected days at the same time on a thread pool
+      --jobs <N>       Like `--parallel`, with at most N threads
+      --bench          Benchmark parse, part 1 and part 2 instead of printing answers
+      --warmup <N>     Unmeasured runs before timing a step [default: 1]
+      --runs <N>       Measured runs per step [default: 10]
+      --timeout <LIMIT> Time limit in seconds for solving a part: `SECS` for every
+                       part, `DAY=SECS` for one day or `DAY.PART=SECS` for one part.
+                       Repeat to combine; a part over its limit is reported as TIMEOUT.
+                       With `--bench`, `--compare` and `report` every timed run
+                       gets the limit, and a part that times out is run only once
+      --memory         Report allocations, bytes allocated and peak memory for every
+                       solved part; needs a build with `--features alloc-stats`
+      --watch          Keep running, solving the selected days again whenever their
+                       input files change and printing which answers changed
+      --format <FMT>   Output format: `text`, or `json` for one record per day, part
+                       and variant [default: text]
+      --malformed <HOW> How day 1 treats lines with something that is not an
+                       integer or more than two values: `error`, `skip`, or
+                       `pad=N` to put N in place of what is unreadable [default: error]
+      --unequal <HOW>  How day 1 treats lines with a single value: `error`, `skip`,
+                       or `pad=N` to put N in the other column [default: error]
+      --lone <COLUMN>  Column of a single value on a day 1 line: `left`, `right`,
+                       or `indent` for right when the line starts with whitespace
+                       [default: left]
+  -h, --help           Print this help";
+
+pub enum Command {
+    Run(Box<Options>),
+    NewDay { day: u8, store: Store },
+    Fetch { day: u8, store: Store },
+    Submit { day: u8, part: Part, answer: String, store: Store },
+    Matrix { input: InputSource, format: Format },
+    Stream { input: InputSource, chunk_len: usize, format: Format },
+    Help,
+}
+
+fn parse_number(token: &str) -> Result<u8, String> {
+    token
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{}` is not a day number", token))
+}
+
+fn parse_day(token: &str, implemented: &[u8]) -> Result<u8, String> {
+    let day = parse_number(token)?;
+    if !implemented.contains(&day) {
+        return Err(format!("day {} is not implemented", day));
+    }
+    Ok(day)
+}
+
+/// Parses the day number given to a subcommand, which may not be implemented yet.
+fn parse_puzzle_day<I: Iterator<Item = String>>(args: &mut I, command: &str) -> Result<u8, String> {
+    let token = args.next().ok_or_else(|| format!("`{}` expects a day number", command))?;
+    let day = parse_number(&token)?;
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {} is not between 1 and 25", day));
+    }
+    Ok(day)
+}
+
+/// The store named by `--store`, or the one found from the environment and config file.
+fn locate_store(store: Option<Store>) -> Result<Store, String> {
+    match store {
+        Some(store) => Ok(store),
+        None => Store::locate().map_err(|err| err.to_string()),
+    }
+}
+
+fn parse_subcommand<I: Iterator<Item = String>>(command: &str, mut args: I) -> Result<Command, String> {
+    let mut store = None;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--store" => store = Some(Store::new(args.next().ok_or("`--store` expects a value")?)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut args = positional.into_iter();
+    let day = parse_puzzle_day(&mut args, command)?;
+    let submission = match command {
+        "new-day" if registry::find(day).is_some() => return Err(format!("day {} is already implemented", day)),
+        "submit" => {
+            let part = match args.next().as_deref() {
+                Some("1") => Part::One,
+                Some("2") => Part::Two,
+                Some(token) => return Err(format!("`{}` is not a part, expected 1 or 2", token)),
+                None => return Err(format!("`{}` expects a part", command)),
+            };
+            let answer = args.next().ok_or_else(|| format!("`{}` expects an answer", command))?;
+            Some((part, answer))
+        }
+        _ => None,
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unexpected argument `{}` after `{}`", extra, command));
+    }
+
+    let store = locate_store(store)?;
+    Ok(match (command, submission) {
+        ("new-day", _) => Command::NewDay { day, store },
+        (_, Some((part, answer))) => Command::Submit { day, part, answer, store },
+        _ => Command::Fetch { day, store },
+    })
+}
+
+/// Parses the arguments of `matrix` or `stream`: an input file, `--format`
+/// and, for `stream`, `--chunk-len`.
+fn parse_file_command<I: Iterator<Item = String>>(command: &str, mut args: I) -> Result<Command, String> {
+    let mut input = None;
+    let mut chunk_len = DEFAULT_CHUNK_LEN;
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let spec = args.next().ok_or("`--format` expects a value")?;
+                format = parse_format(&spec)?;
+            }
+            "--chunk-len" if command == "stream" => {
+                let spec = args.next().ok_or("`--chunk-len` expects a value")?;
+                chunk_len = parse_count(&spec, 1)?;
+            }
+            "-" if input.is_none() => input = Some(InputSource::Stdin),
+            path if input.is_none() => input = Some(InputSource::File(PathBuf::from(path))),
+            _ => return Err(format!("unexpected argument `{}` after `{}`", arg, command)),
+        }
+    }
+    let input = input.ok_or_else(|| format!("`{}` expects an input file", command))?;
+    Ok(match command {
+        "matrix" => Command::Matrix { input, format },
+        _ => Command::Stream { input, chunk_len, format },
+    })
+}
+
+fn parse_days(spec: &str) -> Result<Vec<u8>, String> {
+    let implemented: Vec<u8> = registry::DAYS.iter().map(|day| day.number).collect();
+    parse_days_of(spec, &implemented)
+}
+
+/// Parses a `--day` value, keeping only the `implemented` days.
+fn parse_days_of(spec: &str, implemented: &[u8]) -> Result<Vec<u8>, String> {
+    if spec == "all" {
+        return Ok(implemented.to_vec());
+    }
+
+    let mut days = Vec::new();
+    for token in spec.split(',') {
+        if let Some((first, last)) = token.split_once('-') {
+            let (first, last) = (parse_number(first)?, parse_number(last)?);
+            if first > last {
+                return Err(format!("range `{}` is reversed, expected `{}-{}`", token, last, first));
+            }
+            let len = days.len();
+            days.extend((first..=last).filter(|day| implemented.contains(day)));
+            if days.len() == len {
+                return Err(format!("range `{}` contains no implemented day", token));
+            }
+        } else {
+            days.push(parse_day(token, implemented)?);
+        }
+    }
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+fn parse_parts(spec: &str) -> Result<Vec<Part>, String> {
+    match spec {
+        "1" => Ok(vec![Part::One]),
+        "2" => Ok(vec![Part::Two]),
+        "both" => Ok(vec![Part::One, Part::Two]),
+        _ => Err(format!("`{}` is not a part, expected 1, 2 or both", spec)),
+    }
+}
+
+fn parse_count(spec: &str, min: usize) -> Result<usize, String> {
+    match spec.parse() {
+        Ok(count) if count >= min => Ok(count),
+        _ => Err(format!("`{}` is not a count of at least {}", spec, min)),
+    }
+}
+
+fn parse_format(spec: &str) -> Result<Format, String> {
+    match spec {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("`{}` is not a format, expected text or json", spec)),
+    }
+}
+
+/// Parses a `--malformed` or `--unequal` value.
+fn parse_policy(spec: &str) -> Result<Policy<i128>, String> {
+    match spec {
+        "error" => Ok(Policy::Error),
+        "skip" => Ok(Policy::Truncate),
+        _ => match spec.strip_prefix("pad=").map(str::parse) {
+            Some(Ok(value)) => Ok(Policy::Pad(value)),
+            _ => Err(format!("`{}` is not a policy, expected error, skip or pad=N", spec)),
+        },
+    }
+}
+
+fn parse_lone(spec: &str) -> Result<LoneValue, String> {
+    match spec {
+        "left" => Ok(LoneValue::Left),
+        "right" => Ok(LoneValue::Right),
+        "indent" => Ok(LoneValue::ByIndent),
+        _ => Err(format!("`{}` is not a column, expected left, right or indent", spec)),
+    }
+}
+
+fn set_mode(options: &mut Options, mode: Mode, flag: &str) -> Result<(), String> {
+    if options.mode != Mode::Run && options.mode != mode {
+        return Err(format!("`{}` cannot be combined with another mode", flag));
+    }
+    options.mode = mode;
+    Ok(())
+}
+
+fn check_example(options: &Options, name: &str) -> Result<(), String> {
+    for &number in &options.days {
+        if !options.store.input_path(number, Some(name)).is_file() {
+            let names = options.store.examples(number);
+            return Err(format!(
+                "day {} has no example `{}` in {}, available: {}",
+                number,
+                name,
+                options.store.root().display(),
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_variant(options: &Options, name: &str) -> Result<(), String> {
+    let [number] = options.days[..] else {
+        return Err("`--variant` needs exactly one day selected with `--day`".to_string());
+    };
+    let day = registry::find(number).expect("Selected days are validated");
+    for &part in &options.parts {
+        let names = day.solution.variant_names(part);
+        if !names.contains(&name) {
+            return Err(format!(
+                "day {} part {} has no variant `{}`, available: {}",
+                number,
+                part,
+                name,
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.peekable();
+    if let Some(command) = args.next_if(|arg| ["new-day", "fetch", "submit"].contains(&arg.as_str())) {
+        return parse_subcommand(&command, args);
+    }
+    if let Some(command) = args.next_if(|arg| arg == "matrix" || arg == "stream") {
+        return parse_file_command(&command, args);
+    }
+
+    let mut options = Options::new(Store::new(""));
+    if args.next_if(|arg| arg == "report").is_some() {
+        options.mode = Mode::Report;
+    }
+
+    let mut store = None;
+    let mut answers = None;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("`{}` expects a value", flag))
+        };
+        match arg.as_str() {
+            "-d" | "--day" => options.days = parse_days(&value(&arg)?)?,
+            "-p" | "--part" => options.parts = parse_parts(&value(&arg)?)?,
+            "-i" | "--input" => {
+                options.input = match value(&arg)?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(PathBuf::from(path)),
+                }
+            }
+            "-e" | "--example" => options.input = InputSource::Example(value(&arg)?),
+            "--store" => store = Some(Store::new(value(&arg)?)),
+            "--variant" => options.variant = Some(value(&arg)?),
+            "--compare" => options.compare = true,
+            "--check-variants" => set_mode(&mut options, Mode::CheckVariants, &arg)?,
+            "--verify" => set_mode(&mut options, Mode::Verify, &arg)?,
+            "--answers" => answers = Some(PathBuf::from(value(&arg)?)),
+            "--parallel" => options.jobs = Some(0),
+            "--jobs" => options.jobs = Some(parse_count(&value(&arg)?, 1)?),
+            "--bench" => set_mode(&mut options, Mode::Bench, &arg)?,
+            "--warmup" => options.bench_config.warmup = parse_count(&value(&arg)?, 0)?,
+            "--runs" => options.bench_config.runs = parse_count(&value(&arg)?, 1)?,
+            "--format" => options.format = parse_format(&value(&arg)?)?,
+            "--malformed" => options.read_policy.malformed = parse_policy(&value(&arg)?)?,
+            "--unequal" => options.read_policy.unequal = parse_policy(&value(&arg)?)?,
+            "--lone" => options.read_policy.lone = parse_lone(&value(&arg)?)?,
+            "--timeout" => options.timeouts.add(&value(&arg)?)?,
+            "--memory" => options.memory = true,
+            "--watch" => options.watch = true,
+            "--redact" | "--output" if options.mode != Mode::Report => {
+                return Err(format!("`{}` only works with `report`", arg));
+            }
+            "--redact" => options.redact = true,
+            "--output" => options.output = Some(PathBuf::from(value(&arg)?)),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    if options.jobs.is_some() && options.mode == Mode::Bench {
+        return Err("`--bench` times days one at a time and cannot run them in parallel".to_string());
+    }
+    if options.mode == Mode::Report && (options.jobs.is_some() || options.format == Format::Json) {
+        return Err("`report` times days one at a time and only writes Markdown".to_string());
+    }
+    if options.memory && !memory::ENABLED {
+        return Err("`--memory` needs a build with `--features alloc-stats`".to_string());
+    }
+    if options.memory && options.jobs.is_some() {
+        return Err("`--memory` counts allocations process-wide and cannot run days in parallel".to_string());
+    }
+    if options.memory && matches!(options.mode, Mode::Bench | Mode::Report) {
+        return Err("`--memory` reports on solved parts and does not work with `--bench` or `report`".to_string());
+    }
+    if options.read_policy != ReadPolicy::default() && !options.days.contains(&1) {
+        return Err("`--malformed`, `--unequal` and `--lone` only apply to day 1".to_string());
+    }
+    if options.watch && options.mode != Mode::Run {
+        return Err("`--watch` only works with a plain run".to_string());
+    }
+    if options.watch && options.format == Format::Json {
+        return Err("`--watch` prints text and cannot be combined with `--format json`".to_string());
+    }
+    if options.watch && options.input == InputSource::Stdin {
+        return Err("`--watch` needs input files and cannot read stdin".to_string());
+    }
+    options.store = locate_store(store)?;
+    options.answers = answers.unwrap_or_else(|| options.store.answers_path());
+    if let InputSource::Example(name) = &options.input {
+        check_example(&options, name)?;
+    }
+    if matches!(options.input, InputSource::File(_) | InputSource::Stdin) && options.days.len() != 1 {
+        return Err("`--input` needs exactly one day selected with `--day`".to_string());
+    }
+    if let Some(name) = &options.variant {
+        check_variant(&options, name)?;
+    }
+
+    Ok(Command::Run(Box::new(options)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args = ["--store", "data"].iter().chain(args).map(|arg| arg.to_string());
+        match parse_args(args)? {
+            Command::Run(options) => Ok(*options),
+            _ => panic!("expected a run"),
+        }
+    }
+
+    /// Days with a gap, so that the tests do not change as days are added.
+    const IMPLEMENTED: &[u8] = &[1, 2, 3, 5, 6, 7];
+
+    #[test]
+    fn parses_day_ranges() {
+        assert_eq!(parse_days_of("6", IMPLEMENTED).unwrap(), vec![6]);
+        assert_eq!(parse_days_of("1-3", IMPLEMENTED).unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_days_of("5,1-2,2", IMPLEMENTED).unwrap(), vec![1, 2, 5]);
+        assert_eq!(parse_days_of("3-5", IMPLEMENTED).unwrap(), vec![3, 5]);
+        assert_eq!(parse_days_of("6-30", IMPLEMENTED).unwrap(), vec![6, 7]);
+        assert_eq!(parse_days_of("all", IMPLEMENTED).unwrap(), IMPLEMENTED);
+        assert_eq!(parse_days("all").unwrap().len(), registry::DAYS.len());
+    }
+
+    #[test]
+    fn rejects_bad_days() {
+        let parse_days = |spec| parse_days_of(spec, IMPLEMENTED);
+        assert_eq!(parse_days("5-3").unwrap_err(), "range `5-3` is reversed, expected `3-5`");
+        assert_eq!(parse_days("20-25").unwrap_err(), "range `20-25` contains no implemented day");
+        assert_eq!(parse_days("4").unwrap_err(), "day 4 is not implemented");
+        assert_eq!(parse_days("1,x").unwrap_err(), "`x` is not a day number");
+        assert!(parse_days("1-").is_err());
+    }
+
+    #[test]
+    fn parses_timeouts() {
+        let options = parse(&["--timeout", "10", "--timeout", "6.2=0.5"]).unwrap();
+        assert_eq!(options.timeouts.limit(1, Part::One), Some(Duration::from_secs(10)));
+        assert_eq!(options.timeouts.limit(6, Part::Two), Some(Duration::from_millis(500)));
+        assert!(parse(&["--timeout", "6.3=1"]).is_err());
+        assert_eq!(parse(&["--timeout"]).unwrap_err(), "`--timeout` expects a value");
+    }
+
+    #[test]
+    fn parses_read_policies() {
+        let options = parse(&["--day", "1", "--malformed", "skip", "--unequal", "pad=-3", "--lone", "indent"]).unwrap();
+        let policy = ReadPolicy { malformed: Policy::Truncate, unequal: Policy::Pad(-3), lone: LoneValue::ByIndent };
+        assert_eq!(options.read_policy, policy);
+        assert_eq!(parse(&["--day", "1"]).unwrap().read_policy, ReadPolicy::default());
+        assert_eq!(
+            parse(&["--malformed", "pad"]).unwrap_err(),
+            "`pad` is not a policy, expected error, skip or pad=N"
+        );
+        assert_eq!(parse(&["--lone", "up"]).unwrap_err(), "`up` is not a column, expected left, right or indent");
+        assert_eq!(
+            parse(&["--day", "6", "--unequal", "skip"]).unwrap_err(),
+            "`--malformed`, `--unequal` and `--lone` only apply to day 1"
+        );
+    }
+
+    #[test]
+    fn subcommands_take_a_store() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        let inputs = Store::new("inputs");
+        assert!(matches!(
+            args(&["fetch", "3", "--store", "inputs"]),
+            Ok(Command::Fetch { day: 3, store }) if store == inputs
+        ));
+        assert!(matches!(
+            args(&["submit", "--store", "inputs", "3", "2", "48"]),
+            Ok(Command::Submit { day: 3, part: Part::Two, answer, store }) if answer == "48" && store == inputs
+        ));
+        assert!(matches!(args(&["new-day", "9", "--store", "inputs"]), Ok(Command::NewDay { day: 9, .. })));
+        assert_eq!(args(&["fetch", "3", "--store"]).err().unwrap(), "`--store` expects a value");
+        assert_eq!(
+            args(&["fetch", "3", "4", "--store", "inputs"]).err().unwrap(),
+            "unexpected argument `4` after `fetch`"
+        );
+    }
+
+    #[test]
+    fn parses_file_commands() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(matches!(
+            args(&["stream", "big.txt", "--chunk-len", "1000", "--format", "json"]),
+            Ok(Command::Stream { input: InputSource::File(path), chunk_len: 1000, format: Format::Json })
+                if path == Path::new("big.txt")
+        ));
+        assert!(matches!(
+            args(&["stream", "-"]),
+            Ok(Command::Stream { input: InputSource::Stdin, chunk_len: DEFAULT_CHUNK_LEN, format: Format::Text })
+        ));
+        assert!(matches!(args(&["matrix", "-"]), Ok(Command::Matrix { input: InputSource::Stdin, .. })));
+        assert_eq!(args(&["stream"]).err().unwrap(), "`stream` expects an input file");
+        assert_eq!(args(&["stream", "-", "--chunk-len", "0"]).err().unwrap(), "`0` is not a count of at least 1");
+        assert_eq!(
+            args(&["matrix", "-", "--chunk-len", "5"]).err().unwrap(),
+            "unexpected argument `--chunk-len` after `matrix`"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(parse(&["--frobnicate"]).unwrap_err(), "unknown argument `--frobnicate`");
+        assert_eq!(parse(&["--part", "3"]).unwrap_err(), "`3` is not a part, expected 1, 2 or both");
+        assert_eq!(parse(&["--redact"]).unwrap_err(), "`--redact` only works with `report`");
+        assert!(parse(&["--verify", "--bench"]).is_err());
+        assert!(parse(&["--input", "-"]).is_err());
+        assert!(parse(&["--runs", "0"]).is_err());
+        assert!(parse(&["--format", "yaml"]).is_err());
+        assert!(parse(&["--day", "1", "--input", "-"]).is_ok());
+    }
+}
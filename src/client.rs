@@ -0,0 +1,412 @@
+//! Talks to the Advent of Code website: downloads inputs into the [`Store`]
+//! and submits answers. Inputs already in the store are never downloaded
+//! again, and every judged answer is written to `submissions.toml` in the
+//! store so that it is never submitted again.
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::solution::Part;
+use crate::store::Store;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const YEAR: u16 = 2024;
+
+/// Environment variable holding the `session` cookie of a logged-in browser.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Environment variable overriding the site address, for example to point at a
+/// local mock server.
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// How the site judged a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Answered too soon after the previous attempt, with the wait the site asked for.
+    RateLimited { wait: Option<String> },
+    /// The part was already solved, so the site did not judge the answer.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page returned for a submission.
+    pub fn from_response(page: &str) -> Option<Verdict> {
+        if page.contains("That's the right answer") {
+            Some(Verdict::Right)
+        } else if page.contains("That's not the right answer") {
+            Some(if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else if page.contains("You gave an answer too recently") {
+            let wait = page
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Some(Verdict::RateLimited { wait })
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Verdict::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    /// How the verdict is written to `submissions.toml`, for verdicts worth keeping.
+    fn recorded(&self) -> Option<&'static str> {
+        match self {
+            Verdict::Right => Some("right"),
+            Verdict::Wrong => Some("wrong"),
+            Verdict::TooHigh => Some("too high"),
+            Verdict::TooLow => Some("too low"),
+            Verdict::RateLimited { .. } | Verdict::AlreadySolved => None,
+        }
+    }
+
+    fn from_recorded(value: &str) -> Option<Verdict> {
+        match value {
+            "right" => Some(Verdict::Right),
+            "wrong" => Some(Verdict::Wrong),
+            "too high" => Some(Verdict::TooHigh),
+            "too low" => Some(Verdict::TooLow),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::RateLimited { wait: Some(wait) } => write!(f, "rate limited, wait {}", wait),
+            Verdict::RateLimited { wait: None } => write!(f, "rate limited"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            _ => write!(f, "{}", self.recorded().expect("Judged verdicts are recorded")),
+        }
+    }
+}
+
+/// Result of [`Client::submit`]. `recorded` is set when the verdict came from
+/// an earlier submission and nothing was sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub verdict: Verdict,
+    pub recorded: bool,
+}
+
+/// Result of [`Client::fetch_input`]. `downloaded` is false when the input was
+/// already in the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetch {
+    pub path: PathBuf,
+    pub downloaded: bool,
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+    store: Store,
+    agent: ureq::Agent,
+}
+
+/// Every judged submission, as `[dayN.partP]` tables mapping answers to verdicts.
+fn submissions_path(store: &Store) -> PathBuf {
+    store.root().join("submissions.toml")
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        source: err,
+    }
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>, store: Store) -> Self {
+        Client {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+            store,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("advent2024/", env!("CARGO_PKG_VERSION")))
+                .build(),
+        }
+    }
+
+    /// A client for `store`, taking the session token and base URL from the
+    /// environment first and the config file second.
+    pub fn from_env(store: Store) -> Result<Self, Error> {
+        let config = Config::load()?;
+        let session = std::env::var(SESSION_ENV).ok().or(config.session).ok_or_else(|| Error::Config {
+            path: Config::path().map_or_else(|| "config".to_string(), |path| path.display().to_string()),
+            message: format!("no session token, set {} or `session` in the config file", SESSION_ENV),
+        })?;
+        let base_url = std::env::var(BASE_URL_ENV)
+            .ok()
+            .or(config.base_url)
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Ok(Client::new(base_url, session, store))
+    }
+
+    fn day_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    fn send(&self, request: ureq::Request, form: Option<&[(&str, &str)]>) -> Result<String, Error> {
+        let url = request.url().to_string();
+        let network_error = |message: String| Error::Network {
+            url: url.clone(),
+            message,
+        };
+        let request = request.set("Cookie", &format!("session={}", self.session));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|err| network_error(err.to_string())),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                let detail = body.lines().next().unwrap_or("").trim();
+                Err(network_error(format!("HTTP {} {}", code, detail).trim_end().to_string()))
+            }
+            Err(err) => Err(network_error(err.to_string())),
+        }
+    }
+
+    /// Downloads the real input for `day` into the store, unless the store
+    /// already has a non-empty one.
+    pub fn fetch_input(&self, day: u8) -> Result<Fetch, Error> {
+        let path = self.store.input_path(day, None);
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+            return Ok(Fetch { path, downloaded: false });
+        }
+
+        let url = format!("{}/input", self.day_url(day));
+        let input = self.send(self.agent.get(&url), None)?;
+        if input.is_empty() {
+            return Err(Error::Network {
+                url,
+                message: "the input is empty".to_string(),
+            });
+        }
+        fs::create_dir_all(self.store.root()).map_err(|err| io_error(self.store.root(), err))?;
+        fs::write(&path, input).map_err(|err| io_error(&path, err))?;
+        Ok(Fetch { path, downloaded: true })
+    }
+
+    fn load_submissions(&self) -> Result<toml::Table, Error> {
+        let path = submissions_path(&self.store);
+        match fs::read_to_string(&path) {
+            Ok(raw) => raw.parse().map_err(|err: toml::de::Error| Error::Config {
+                path: path.display().to_string(),
+                message: err.message().trim().to_string(),
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+            Err(err) => Err(io_error(&path, err)),
+        }
+    }
+
+    /// The verdict recorded for `answer`, if it was judged before or follows
+    /// from earlier verdicts: once a part has a right answer every other one is
+    /// wrong, and a number beyond one already too high or too low is too.
+    fn recorded_verdict(submissions: &toml::Table, day: u8, part: Part, answer: &str) -> Option<Verdict> {
+        let answers = submissions
+            .get(&format!("day{}", day))?
+            .get(format!("part{}", part))?
+            .as_table()?;
+        if let Some(verdict) = answers.get(answer) {
+            return verdict.as_str().and_then(Verdict::from_recorded);
+        }
+
+        let number: Option<i128> = answer.parse().ok();
+        let mut deduced = None;
+        for (earlier, verdict) in answers {
+            let verdict = verdict.as_str().and_then(Verdict::from_recorded);
+            let bound: Option<i128> = earlier.parse().ok();
+            match (verdict, number.zip(bound)) {
+                (Some(Verdict::Right), _) => return Some(Verdict::Wrong),
+                (Some(Verdict::TooHigh), Some((number, bound))) if number >= bound => {
+                    deduced = Some(Verdict::TooHigh)
+                }
+                (Some(Verdict::TooLow), Some((number, bound))) if number <= bound => {
+                    deduced = Some(Verdict::TooLow)
+                }
+                _ => {}
+            }
+        }
+        deduced
+    }
+
+    /// Submits `answer` for `part` of `day`, unless its verdict can be told
+    /// from earlier submissions.
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Submission, Error> {
+        let mut submissions = self.load_submissions()?;
+        if let Some(verdict) = Client::recorded_verdict(&submissions, day, part, answer) {
+            return Ok(Submission { verdict, recorded: true });
+        }
+
+        let url = format!("{}/answer", self.day_url(day));
+        let level = part.to_string();
+        let page = self.send(self.agent.post(&url), Some(&[("level", &level), ("answer", answer)]))?;
+        let verdict = Verdict::from_response(&page).ok_or_else(|| Error::Network {
+            url,
+            message: "unable to find the verdict in the response".to_string(),
+        })?;
+
+        if let Some(recorded) = verdict.recorded() {
+            let answers = submissions
+                .entry(format!("day{}", day))
+                .or_insert_with(|| toml::Table::new().into())
+                .as_table_mut()
+                .and_then(|parts| {
+                    parts
+                        .entry(format!("part{}", part))
+                        .or_insert_with(|| toml::Table::new().into())
+                        .as_table_mut()
+                });
+            let path = submissions_path(&self.store);
+            let answers = answers.ok_or_else(|| Error::Config {
+                path: path.display().to_string(),
+                message: format!("`day{}.part{}` must be a table", day, part),
+            })?;
+            answers.insert(answer.to_string(), recorded.into());
+            fs::create_dir_all(self.store.root()).map_err(|err| io_error(self.store.root(), err))?;
+            fs::write(&path, submissions.to_string()).map_err(|err| io_error(&path, err))?;
+        }
+
+        Ok(Submission { verdict, recorded: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// A server on a local port answering every request with `body`. Returns
+    /// its address and the request lines it has seen.
+    fn mock_server(body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut form = vec![0; length];
+                reader.read_exact(&mut form).unwrap();
+                let request = format!("{} {}", request_line.trim(), String::from_utf8(form).unwrap());
+                seen.lock().unwrap().push(request.trim().to_string());
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (address, requests)
+    }
+
+    fn temp_store(name: &str) -> Store {
+        let root = std::env::temp_dir().join(format!("advent2024-client-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Store::new(root)
+    }
+
+    #[test]
+    fn reads_verdicts() {
+        let verdict = |page| Verdict::from_response(page);
+        assert_eq!(verdict("<p>That's the right answer! You are one gold star closer"), Some(Verdict::Right));
+        assert_eq!(
+            verdict("That's not the right answer; your answer is too high. Please wait"),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(verdict("That's not the right answer; your answer is too low."), Some(Verdict::TooLow));
+        assert_eq!(verdict("That's not the right answer. If you're stuck"), Some(Verdict::Wrong));
+        assert_eq!(
+            verdict("You gave an answer too recently; you have to wait. You have 4m 12s left to wait."),
+            Some(Verdict::RateLimited { wait: Some("4m 12s".to_string()) })
+        );
+        assert_eq!(verdict("You don't seem to be solving the right level."), Some(Verdict::AlreadySolved));
+        assert_eq!(verdict("<html>maintenance</html>"), None);
+    }
+
+    #[test]
+    fn caches_inputs() {
+        let (address, requests) = mock_server("3   4\n4   3\n");
+        let store = temp_store("fetch");
+        let client = Client::new(address, "token", store.clone());
+
+        let first = client.fetch_input(1).unwrap();
+        let second = client.fetch_input(1).unwrap();
+        assert!(first.downloaded && !second.downloaded);
+        assert_eq!(fs::read_to_string(&first.path).unwrap(), "3   4\n4   3\n");
+        assert_eq!(*requests.lock().unwrap(), vec!["GET /2024/day/1/input HTTP/1.1"]);
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn records_submissions() {
+        let (address, requests) = mock_server("<p>That's the right answer!</p>");
+        let store = temp_store("submit");
+        let client = Client::new(address, "token", store.clone());
+
+        let first = client.submit(1, Part::Two, "31").unwrap();
+        let again = client.submit(1, Part::Two, "31").unwrap();
+        let other = client.submit(1, Part::Two, "30").unwrap();
+        assert_eq!(first, Submission { verdict: Verdict::Right, recorded: false });
+        assert_eq!(again, Submission { verdict: Verdict::Right, recorded: true });
+        assert_eq!(other, Submission { verdict: Verdict::Wrong, recorded: true });
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec!["POST /2024/day/1/answer HTTP/1.1 level=2&answer=31"]
+        );
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn deduces_from_bounds() {
+        let submissions: toml::Table = "[day6.part1]\n40 = \"too low\"\n50 = \"too high\"\n".parse().unwrap();
+        let verdict = |answer| Client::recorded_verdict(&submissions, 6, Part::One, answer);
+        assert_eq!(verdict("40"), Some(Verdict::TooLow));
+        assert_eq!(verdict("12"), Some(Verdict::TooLow));
+        assert_eq!(verdict("51"), Some(Verdict::TooHigh));
+        assert_eq!(verdict("41"), None);
+        assert_eq!(verdict("abc"), None);
+        assert_eq!(Client::recorded_verdict(&submissions, 6, Part::Two, "41"), None);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::Error;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the config file, which otherwise lives at
+/// `$XDG_CONFIG_HOME/advent2024/config.toml` or `~/.config/advent2024/config.toml`.
+pub const CONFIG_ENV: &str = "AOC_CONFIG";
+
+/// Settings from the user's config file, for example
+///
+/// ```toml
+/// store = "inputs"
+/// session = "53616c7465645f5f..."
+/// base_url = "http://localhost:8080"
+/// ```
+///
+/// A relative `store` is resolved against the config file's directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub store: Option<PathBuf>,
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+fn string_entry(table: &toml::Table, key: &str) -> Result<Option<String>, String> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(toml::Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("`{}` must be a string", key)),
+    }
+}
+
+impl Config {
+    /// Where the config file is looked for.
+    pub fn path() -> Option<PathBuf> {
+        Config::locate().map(|(path, _)| path)
+    }
+
+    /// The config file's path, and whether it was named by `$AOC_CONFIG`.
+    fn locate() -> Option<(PathBuf, bool)> {
+        if let Some(path) = env::var_os(CONFIG_ENV) {
+            return Some((PathBuf::from(path), true));
+        }
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some((config_home.join("advent2024").join("config.toml"), false))
+    }
+
+    /// Reads the config file. A missing file at the default location gives an
+    /// empty config, but one named by `$AOC_CONFIG` has to exist.
+    pub fn load() -> Result<Self, Error> {
+        match Config::locate() {
+            Some((path, explicit)) => Config::read(&path, explicit),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Reads the config file at `path`, which may only be missing if it was
+    /// not `explicit`ly named.
+    fn read(path: &Path, explicit: bool) -> Result<Self, Error> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound && !explicit => return Ok(Config::default()),
+            Err(err) => {
+                return Err(Error::Io {
+                    path: path.display().to_string(),
+                    source: err,
+                })
+            }
+        };
+        Config::from_toml(path, &raw).map_err(|message| Error::Config {
+            path: path.display().to_string(),
+            message,
+        })
+    }
+
+    /// Parses the contents `raw` of the config file at `path`.
+    pub fn from_toml(path: &Path, raw: &str) -> Result<Self, String> {
+        let table: toml::Table = raw
+            .parse()
+            .map_err(|err: toml::de::Error| err.message().trim().to_string())?;
+        for key in table.keys() {
+            if !["store", "session", "base_url"].contains(&key.as_str()) {
+                return Err(format!("unknown key `{}`, expected `store`, `session` or `base_url`", key));
+            }
+        }
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        Ok(Config {
+            store: string_entry(&table, "store")?.map(|store| dir.join(store)),
+            session: string_entry(&table, "session")?,
+            base_url: string_entry(&table, "base_url")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_entries() {
+        let path = Path::new("/home/me/.config/advent2024/config.toml");
+        let config = Config::from_toml(path, "store = \"/srv/aoc\"\nsession = \"abc\"\n").unwrap();
+        assert_eq!(config.store, Some(PathBuf::from("/srv/aoc")));
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.base_url, None);
+        assert_eq!(Config::from_toml(path, ""), Ok(Config::default()));
+    }
+
+    #[test]
+    fn resolves_relative_store() {
+        let path = Path::new("/home/me/.config/advent2024/config.toml");
+        assert_eq!(
+            Config::from_toml(path, "store = \"inputs\"\n").unwrap().store,
+            Some(PathBuf::from("/home/me/.config/advent2024/inputs"))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_entries() {
+        let path = Path::new("config.toml");
+        assert_eq!(Config::from_toml(path, "store = 3\n"), Err("`store` must be a string".to_string()));
+        assert!(Config::from_toml(path, "token = \"abc\"\n").is_err());
+    }
+
+    #[test]
+    fn only_a_default_config_may_be_missing() {
+        let missing = env::temp_dir().join("advent2024-no-such-config.toml");
+        assert_eq!(Config::read(&missing, false).unwrap(), Config::default());
+        let err = Config::read(&missing, true).unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+        assert!(!err.to_string().contains("input"), "{}", err);
+        assert!(matches!(Config::read(&env::temp_dir(), false), Err(Error::Io { .. })));
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+/// A compass direction on a [`crate::grid::Grid`], where north is up (row - 1)
+/// and east is right (column + 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The 4 orthogonal directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+
+    /// All 8 directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest];
+
+    fn from_index(index: usize) -> Direction {
+        Self::ALL[index % 8]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// `(row, column)` offset of one step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            North => (-1, 0),
+            NorthEast => (-1, 1),
+            East => (0, 1),
+            SouthEast => (1, 1),
+            South => (1, 0),
+            SouthWest => (1, -1),
+            West => (0, -1),
+            NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// Quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        Self::from_index(self.index() + 2)
+    }
+
+    /// Quarter turn anticlockwise.
+    pub fn turn_left(self) -> Direction {
+        Self::from_index(self.index() + 6)
+    }
+
+    pub fn turn_around(self) -> Direction {
+        Self::from_index(self.index() + 4)
+    }
+
+    /// Eighth of a turn clockwise.
+    pub fn turn_right_45(self) -> Direction {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// Eighth of a turn anticlockwise.
+    pub fn turn_left_45(self) -> Direction {
+        Self::from_index(self.index() + 7)
+    }
+
+    /// Mirror image across the north-south axis, swapping east and west.
+    pub fn flip_horizontal(self) -> Direction {
+        Self::from_index(8 - self.index())
+    }
+
+    /// Mirror image across the east-west axis, swapping north and south.
+    pub fn flip_vertical(self) -> Direction {
+        Self::from_index(12 - self.index())
+    }
+
+    /// Parses one of the arrows `^`, `>`, `v` and `<`.
+    pub fn from_arrow(ch: char) -> Option<Direction> {
+        match ch {
+            '^' => Some(North),
+            '>' => Some(East),
+            'v' => Some(South),
+            '<' => Some(West),
+            _ => None,
+        }
+    }
+
+    /// The arrow for an orthogonal direction.
+    pub fn arrow(self) -> Option<char> {
+        match self {
+            North => Some('^'),
+            East => Some('>'),
+            South => Some('v'),
+            West => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            North => "N",
+            NorthEast => "NE",
+            East => "E",
+            SouthEast => "SE",
+            South => "S",
+            SouthWest => "SW",
+            West => "W",
+            NorthWest => "NW",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(SouthWest.turn_around(), NorthEast);
+        assert_eq!(NorthWest.turn_right_45(), North);
+        assert_eq!(North.turn_left_45(), NorthWest);
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_around().turn_around(), dir);
+        }
+    }
+
+    #[test]
+    fn flips() {
+        assert_eq!(East.flip_horizontal(), West);
+        assert_eq!(NorthEast.flip_horizontal(), NorthWest);
+        assert_eq!(North.flip_horizontal(), North);
+        assert_eq!(North.flip_vertical(), South);
+        assert_eq!(SouthEast.flip_vertical(), NorthEast);
+        assert_eq!(West.flip_vertical(), West);
+    }
+
+    #[test]
+    fn deltas_match_turns() {
+        for dir in Direction::ALL {
+            let (row, col) = dir.delta();
+            assert_eq!(dir.turn_around().delta(), (-row, -col));
+            assert_eq!(dir.flip_horizontal().delta(), (row, -col));
+            assert_eq!(dir.is_diagonal(), row != 0 && col != 0);
+        }
+    }
+
+    #[test]
+    fn arrows_round_trip() {
+        for dir in Direction::CARDINAL {
+            assert_eq!(Direction::from_arrow(dir.arrow().unwrap()), Some(dir));
+        }
+        assert_eq!(Direction::from_arrow('.'), None);
+        assert_eq!(NorthEast.arrow(), None);
+    }
+}
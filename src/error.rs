@@ -0,0 +1,111 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// A problem found while parsing puzzle input. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: impl Into<String>, found: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// Error for a token cut out of `line`, with the column taken from where the token sits.
+    pub fn at(line_no: usize, line: &str, token: &str, expected: impl Into<String>) -> Self {
+        let found = if token.is_empty() {
+            "nothing".to_string()
+        } else {
+            format!("`{}`", token)
+        };
+        ParseError::new(line_no, column_of(line, token), expected, found)
+    }
+
+    /// Error for a line that stopped before something required.
+    pub fn end_of_line(line_no: usize, line: &str, expected: impl Into<String>) -> Self {
+        ParseError::new(line_no, line.chars().count() + 1, expected, "end of line")
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: expected {}, found {}",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A running total that no longer fits in the type it is kept in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    /// What was being added up, such as "the total distance".
+    pub total: &'static str,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} overflowed", self.total)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Everything that can stop a day from producing its answers.
+#[derive(Debug)]
+pub enum Error {
+    Io { path: String, source: io::Error },
+    Parse { path: String, source: ParseError },
+    Overflow { path: String, source: Overflow },
+    Config { path: String, message: String },
+    Network { url: String, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path, source),
+            Error::Parse { path, source } => write!(f, "{}:{}", path, source),
+            Error::Overflow { path, source } => write!(f, "{}: {}", path, source),
+            Error::Config { path, message } => write!(f, "{}: {}", path, message),
+            Error::Network { url, message } => write!(f, "{}: {}", url, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { source, .. } => Some(source),
+            Error::Overflow { source, .. } => Some(source),
+            Error::Config { .. } | Error::Network { .. } => None,
+        }
+    }
+}
+
+/// 1-based character column of `token`, which must be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+/// Parses `token`, a slice of `line`, reporting its position if it is not a valid number.
+pub fn parse_number<T: FromStr>(line_no: usize, line: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(line_no, line, token, "an integer"))
+}
@@ -0,0 +1,315 @@
+//! Sorting more integers than fit in memory. An [`ExternalSorter`] collects
+//! values into chunks of bounded length, sorts each chunk and spills it to a
+//! temporary file as a sorted run. The finished [`SortedRuns`] then merge the
+//! runs back into one ascending stream, which can be read any number of times.
+//! At most [`MAX_FAN_IN`] runs are ever open at once: when there are more,
+//! `finish` first merges them in groups into longer runs, as often as needed.
+//! Any integer type up to `i128` can be sorted; runs always hold `i128`.
+
+use crate::error::Error;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Values held in memory at once by default, 8 MiB worth of `i64`.
+pub const DEFAULT_CHUNK_LEN: usize = 1 << 20;
+
+/// Runs merged at once, each with its own open file and read buffer.
+pub const MAX_FAN_IN: usize = 64;
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Values an [`ExternalSorter`] can sort: integers of any type up to `i128`.
+pub trait RunValue: Copy + Ord + Into<i128> + TryFrom<i128> {}
+
+impl<T: Copy + Ord + Into<i128> + TryFrom<i128>> RunValue for T {}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> Error + '_ {
+    move |err| Error::Io {
+        path: path.display().to_string(),
+        source: err,
+    }
+}
+
+/// A directory of run files, removed with everything in it when dropped.
+#[derive(Debug)]
+struct SpillDir {
+    path: PathBuf,
+}
+
+impl SpillDir {
+    fn create() -> Result<Self, Error> {
+        let name = format!(
+            "advent2024-sort-{}-{}",
+            process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = env::temp_dir().join(name);
+        fs::create_dir_all(&path).map_err(io_error(&path))?;
+        Ok(SpillDir { path })
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Sorts values pushed one at a time, keeping at most `chunk_len` of them in
+/// memory.
+#[derive(Debug)]
+pub struct ExternalSorter<T> {
+    chunk_len: usize,
+    fan_in: usize,
+    chunk: Vec<T>,
+    dir: SpillDir,
+    runs: Vec<PathBuf>,
+    /// Number of the next run file, so that merged runs get fresh names.
+    next_run: usize,
+    len: usize,
+}
+
+impl<T: RunValue> ExternalSorter<T> {
+    pub fn new(chunk_len: usize) -> Result<Self, Error> {
+        let chunk_len = chunk_len.max(1);
+        Ok(ExternalSorter {
+            chunk_len,
+            fan_in: MAX_FAN_IN,
+            chunk: Vec::with_capacity(chunk_len.min(DEFAULT_CHUNK_LEN)),
+            dir: SpillDir::create()?,
+            runs: Vec::new(),
+            next_run: 0,
+            len: 0,
+        })
+    }
+
+    /// Merges at most `fan_in` runs at once instead of [`MAX_FAN_IN`].
+    pub fn with_fan_in(mut self, fan_in: usize) -> Self {
+        self.fan_in = fan_in.max(2);
+        self
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        self.chunk.push(value);
+        self.len += 1;
+        if self.chunk.len() >= self.chunk_len {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Sorts the current chunk and writes it out as a new run.
+    fn spill(&mut self) -> Result<(), Error> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        self.chunk.sort_unstable();
+        let path = self.run_path();
+        write_run(&path, self.chunk.drain(..).map(Ok))?;
+        self.runs.push(path);
+        Ok(())
+    }
+
+    fn run_path(&mut self) -> PathBuf {
+        self.next_run += 1;
+        self.dir.path.join(format!("run{}.bin", self.next_run - 1))
+    }
+
+    /// Merges the runs in groups of `fan_in` into longer runs, deleting the
+    /// merged ones.
+    fn merge_pass(&mut self) -> Result<(), Error> {
+        let runs = std::mem::take(&mut self.runs);
+        for group in runs.chunks(self.fan_in) {
+            let path = self.run_path();
+            write_run(&path, Merge::<T>::open(group)?)?;
+            for run in group {
+                fs::remove_file(run).map_err(io_error(run))?;
+            }
+            self.runs.push(path);
+        }
+        Ok(())
+    }
+
+    /// Spills what is left, merges the runs down to at most `fan_in` and
+    /// hands them back.
+    pub fn finish(mut self) -> Result<SortedRuns<T>, Error> {
+        self.spill()?;
+        while self.runs.len() > self.fan_in {
+            self.merge_pass()?;
+        }
+        Ok(SortedRuns {
+            runs: std::mem::take(&mut self.runs),
+            len: self.len,
+            _dir: self.dir,
+            _values: PhantomData,
+        })
+    }
+}
+
+/// Every value pushed into an [`ExternalSorter`], as sorted runs on disk.
+#[derive(Debug)]
+pub struct SortedRuns<T> {
+    runs: Vec<PathBuf>,
+    len: usize,
+    _dir: SpillDir,
+    _values: PhantomData<T>,
+}
+
+impl<T: RunValue> SortedRuns<T> {
+    /// How many values were sorted.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// How many runs were spilled.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Opens every run and merges them into one ascending stream. Each call
+    /// reads the runs from the start, independently of earlier ones.
+    pub fn merged(&self) -> Result<Merge<'_, T>, Error> {
+        Merge::open(&self.runs)
+    }
+}
+
+/// Writes `values`, which must be ascending, as a run file at `path`.
+fn write_run<T: RunValue>(path: &Path, values: impl Iterator<Item = Result<T, Error>>) -> Result<(), Error> {
+    let file = File::create(path).map_err(io_error(path))?;
+    let mut writer = BufWriter::new(file);
+    for value in values {
+        let value: i128 = value?.into();
+        writer.write_all(&value.to_le_bytes()).map_err(io_error(path))?;
+    }
+    writer.flush().map_err(io_error(path))
+}
+
+/// The ascending stream of values from [`SortedRuns::merged`].
+pub struct Merge<'a, T> {
+    readers: Vec<(&'a PathBuf, BufReader<File>)>,
+    /// The next unread value of every run that has one, smallest first.
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+}
+
+impl<'a, T: RunValue> Merge<'a, T> {
+    fn open(runs: &'a [PathBuf]) -> Result<Self, Error> {
+        let mut merge = Merge {
+            readers: Vec::with_capacity(runs.len()),
+            heap: BinaryHeap::with_capacity(runs.len()),
+        };
+        for path in runs {
+            let file = File::open(path).map_err(io_error(path))?;
+            merge.readers.push((path, BufReader::new(file)));
+            merge.refill(merge.readers.len() - 1)?;
+        }
+        Ok(merge)
+    }
+
+    /// Reads the next value of run `index` into the heap, if there is one.
+    fn refill(&mut self, index: usize) -> Result<(), Error> {
+        let (path, reader) = &mut self.readers[index];
+        let mut bytes = [0; 16];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => {
+                let value = T::try_from(i128::from_le_bytes(bytes)).map_err(|_| {
+                    io_error(path)(io::Error::new(ErrorKind::InvalidData, "value out of range for the run"))
+                })?;
+                self.heap.push(Reverse((value, index)));
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => Ok(()),
+            Err(err) => Err(io_error(path)(err)),
+        }
+    }
+}
+
+impl<T: RunValue> Iterator for Merge<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((value, index)) = self.heap.pop()?;
+        Some(self.refill(index).map(|()| value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_spilled_runs() {
+        let values = [5, -3, 9, 0, 5, 12, -7, 1, 8, 3];
+        let mut sorter = ExternalSorter::new(3).unwrap();
+        for value in values {
+            sorter.push(value).unwrap();
+        }
+        let runs = sorter.finish().unwrap();
+        assert_eq!((runs.len(), runs.run_count()), (10, 4));
+
+        let mut expected = values.to_vec();
+        expected.sort();
+        for _ in 0..2 {
+            let merged: Vec<i64> = runs.merged().unwrap().collect::<Result<_, _>>().unwrap();
+            assert_eq!(merged, expected);
+        }
+    }
+
+    #[test]
+    fn merges_in_passes_above_the_fan_in() {
+        let values: Vec<i64> = (0..50).map(|i| (i * 37) % 23 - 11).collect();
+        let mut sorter = ExternalSorter::new(2).unwrap().with_fan_in(3);
+        for &value in &values {
+            sorter.push(value).unwrap();
+        }
+        let dir = sorter.dir.path.clone();
+        let runs = sorter.finish().unwrap();
+        assert_eq!(runs.len(), 50);
+        assert!(runs.run_count() <= 3);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), runs.run_count());
+
+        let mut expected = values;
+        expected.sort();
+        let merged: Vec<i64> = runs.merged().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn sorts_values_of_any_width() {
+        let values = [u64::MAX, 0, 1 << 63, 7, u64::MAX - 1];
+        let mut sorter = ExternalSorter::new(2).unwrap();
+        for value in values {
+            sorter.push(value).unwrap();
+        }
+        let runs = sorter.finish().unwrap();
+        let merged: Vec<u64> = runs.merged().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(merged, [0, 7, 1 << 63, u64::MAX - 1, u64::MAX]);
+    }
+
+    #[test]
+    fn removes_its_files() {
+        let mut sorter = ExternalSorter::new(2).unwrap();
+        for value in [3, 1, 2] {
+            sorter.push(value).unwrap();
+        }
+        let dir = sorter.dir.path.clone();
+        let runs = sorter.finish().unwrap();
+        assert!(dir.join("run1.bin").is_file());
+        drop(runs);
+        assert!(!dir.exists());
+
+        let empty = ExternalSorter::<i64>::new(2).unwrap().finish().unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.merged().unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::direction::Direction;
+use crate::error::ParseError;
+use std::ops::{Index, IndexMut};
+
+/// A `(row, column)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// A rectangular grid stored row by row in a flat buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// Parses one row per line, failing on the first row whose length differs
+    /// from the first one.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (line_no, line) in (1..).zip(input.lines()) {
+            let len = cells.len();
+            cells.extend(line.chars());
+            let row_width = cells.len() - len;
+            let width = *width.get_or_insert(row_width);
+            if row_width != width {
+                return Err(ParseError::new(
+                    line_no,
+                    row_width.min(width) + 1,
+                    format!("a row of {} cells", width),
+                    format!("{} cells", row_width),
+                ));
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from row-major `cells`, which must hold exactly
+    /// `width * height` values.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == width * height).then_some(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, row: isize, col: isize) -> bool {
+        0 <= row && (row as usize) < self.height && 0 <= col && (col as usize) < self.width
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        if row < self.height && col < self.width {
+            Some(&mut self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// The position one `(row, column)` step away from `pos`, if it is inside the grid.
+    pub fn step(&self, (row, col): Pos, (d_row, d_col): (isize, isize)) -> Option<Pos> {
+        let (row, col) = (row as isize + d_row, col as isize + d_col);
+        self.in_bounds(row, col).then_some((row as usize, col as usize))
+    }
+
+    /// Positions from `start` onwards, moving by `delta` until leaving the grid.
+    pub fn ray(&self, start: Pos, delta: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        let first = self.get(start).map(|_| start);
+        std::iter::successors(first, move |&pos| self.step(pos, delta))
+    }
+
+    /// The up to 4 orthogonal neighbours of `pos`, clockwise from north.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::CARDINAL
+            .iter()
+            .filter_map(move |dir| self.step(pos, dir.delta()))
+    }
+
+    /// The up to 8 orthogonal and diagonal neighbours of `pos`, clockwise from north.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |dir| self.step(pos, dir.delta()))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// The cells of row `row` from left to right, or `None` if there is no such row.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The cells of column `col` from top to bottom, or `None` if there is no such column.
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        (col < self.width).then(|| self.cells[col..].iter().step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.cells[col..].iter().step_by(self.width))
+    }
+
+    /// Diagonals running down and to the right, starting from the bottom-left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|row| (row, 0))
+            .chain((1..self.width).map(|col| (0, col)));
+        starts.map(move |start| self.ray(start, (1, 1)).map(move |pos| &self[pos]))
+    }
+
+    /// Diagonals running down and to the left, starting from the top-left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .map(|col| (0, col))
+            .chain((1..self.height).map(|row| (row, self.width.saturating_sub(1))));
+        starts.map(move |start| self.ray(start, (1, -1)).map(move |pos| &self[pos]))
+    }
+
+    /// Position of the first cell, row by row, that satisfies `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        let index = self.cells.iter().position(predicate)?;
+        Some((index / self.width, index % self.width))
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Position of the first cell, row by row, equal to `value`.
+    pub fn find(&self, value: &T) -> Option<Pos> {
+        self.position(|cell| cell == value)
+    }
+
+    /// Positions of every cell equal to `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a {
+        self.positions().filter(move |&pos| self[pos] == *value)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Pos) -> &T {
+        assert!(row < self.height && col < self.width, "({}, {}) is outside the grid", row, col);
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Pos) -> &mut T {
+        assert!(row < self.height && col < self.width, "({}, {}) is outside the grid", row, col);
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parses_rows() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid[(1, 2)], 'f');
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = Grid::parse("abc\nde\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn get_is_bounds_checked() {
+        let grid = sample();
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.step((0, 0), (-1, 0)), None);
+        assert_eq!(grid.step((0, 0), (1, 1)), Some((1, 1)));
+        assert_eq!(grid.column(2).map(|cells| cells.collect::<String>()), Some("cf".to_string()));
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.row(2), None);
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = sample();
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = sample();
+        let collect = |cells: &mut dyn Iterator<Item = &char>| cells.collect::<String>();
+        let columns: Vec<String> = grid.columns().map(|mut c| collect(&mut c)).collect();
+        let diagonals: Vec<String> = grid.diagonals().map(|mut d| collect(&mut d)).collect();
+        let anti_diagonals: Vec<String> = grid.anti_diagonals().map(|mut d| collect(&mut d)).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(diagonals, vec!["d", "ae", "bf", "c"]);
+        assert_eq!(anti_diagonals, vec!["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn finds_cells() {
+        let grid = Grid::parse("a.a\n.a.\n").unwrap();
+        assert_eq!(grid.find(&'.'), Some((0, 1)));
+        assert_eq!(grid.find(&'x'), None);
+        assert_eq!(grid.find_all(&'a').collect::<Vec<_>>(), vec![(0, 0), (0, 2), (1, 1)]);
+    }
+}
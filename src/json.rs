@@ -0,0 +1,39 @@
+/// Quotes and escapes `value` as a JSON string literal.
+pub fn string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_plain_text() {
+        assert_eq!(string("part 1 [serial]"), "\"part 1 [serial]\"");
+        assert_eq!(string(""), "\"\"");
+        assert_eq!(string("héllo"), "\"héllo\"");
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(string("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(string(r"C:\inputs"), r#""C:\\inputs""#);
+        assert_eq!(string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(string("\u{0}\u{1b}\u{1f}"), r#""\u0000\u001b\u001f""#);
+        assert_eq!(string("\u{7f}"), "\"\u{7f}\"");
+    }
+}
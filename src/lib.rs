@@ -0,0 +1,33 @@
+//! Advent of Code 2024 solutions.
+//!
+//! Every day lives in its own `aocN` module and implements
+//! [`solution::Solution`]; [`registry::DAYS`] lists them all.
+
+pub mod aoc1;
+pub mod aoc2;
+pub mod aoc3;
+pub mod aoc4;
+pub mod aoc5;
+pub mod aoc6;
+pub mod aoc7;
+pub mod bench;
+pub mod cancel;
+pub mod client;
+pub mod config;
+pub mod direction;
+pub mod error;
+pub mod external_sort;
+pub mod grid;
+pub mod json;
+pub mod memory;
+pub mod pair_index;
+pub mod parse;
+pub mod record;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod store;
+pub mod verify;
+pub mod watch;
@@ -1,17 +1,28 @@
-mod aoc1;
-mod aoc2;
-mod aoc3;
-mod aoc4;
-mod aoc5;
-mod aoc6;
-mod aoc7;
+mod cli;
+
+use advent2024::runner;
+use cli::Command;
+use std::{env, process};
 
 fn main() {
-    aoc1::run();
-    aoc2::run();
-    aoc3::run();
-    aoc4::run();
-    aoc5::run();
-    aoc6::run();
-    aoc7::run();
+    let succeeded = match cli::parse_args(env::args().skip(1)) {
+        Ok(Command::Run(options)) => runner::run(&options),
+        Ok(Command::NewDay { day, store }) => runner::new_day(day, &store),
+        Ok(Command::Fetch { day, store }) => runner::fetch(day, store),
+        Ok(Command::Submit { day, part, answer, store }) => runner::submit(day, part, &answer, store),
+        Ok(Command::Matrix { input, format }) => runner::matrix(&input, format),
+        Ok(Command::Stream { input, chunk_len, format }) => runner::stream(&input, chunk_len, format),
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            true
+        }
+        Err(msg) => {
+            eprintln!("error: {}\nRun with `--help` to see every option.", msg);
+            process::exit(2);
+        }
+    };
+
+    if !succeeded {
+        process::exit(1);
+    }
 }
@@ -0,0 +1,463 @@
+//! An index over a pair of lists that keeps the day 1 answers, as computed by
+//! `aoc1::return_dist` and `aoc1::similarity_score`, current while values are
+//! inserted into and removed from either list.
+//!
+//! The similarity only needs how often each value occurs in both lists, so it
+//! changes by one product per update. The distance of two sorted lists of
+//! equal length is the area between their counting functions: the sum over
+//! the gaps between consecutive distinct values of `|D| * gap`, where `D` is
+//! how many more left values than right ones lie below the gap. Adding a value
+//! moves `D` by one in every gap above it, and the area changes by the width
+//! of those gaps where `D` was at least 0 minus the width of those where it
+//! was below. Keeping that split current under such shifts takes more than a
+//! fixed summary per tree node, so the index splits the distinct values into
+//! blocks of about `sqrt(n)` consecutive ones instead. Every block keeps its
+//! gaps sorted by `D` relative to the start of the block, which gives its area
+//! for any `D` at the start with one binary search. An update rebuilds the
+//! block holding the value and adds up the areas of all blocks.
+//!
+//! Counts and the similarity are therefore kept in O(log n) per update, but
+//! the distance takes O(sqrt(n) log n), however the values are spread. `n` is
+//! the number of distinct values held now: blocks that removals left too
+//! small are merged again, all at once when there are more than twice as many
+//! blocks as needed, so that cost is amortized.
+//!
+//! The values can be of any [`ListValue`] type. Unlike the checked totals of
+//! `aoc1`, both answers are kept as plain `i128`, which is wide enough for
+//! lists of millions of 64-bit values.
+
+use crate::aoc1::ListValue;
+
+/// One of the two lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn index(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+        }
+    }
+}
+
+/// Blocks are never split below this many values.
+const MIN_BLOCK_LEN: usize = 16;
+
+/// A distinct value of either list.
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    key: T,
+    /// How often `key` occurs in each list.
+    counts: [usize; 2],
+}
+
+/// A run of consecutive distinct values.
+#[derive(Debug, Clone)]
+struct Block<T> {
+    entries: Vec<Entry<T>>,
+    /// How many more left values than right ones the block holds.
+    surplus: i64,
+    /// `D` relative to the start of the block and the width of every gap
+    /// between two of its entries, sorted by `D`.
+    levels: Vec<(i64, i128)>,
+    /// Prefix sums over `levels` of the widths and of `D` times the widths.
+    widths: Vec<i128>,
+    weighted: Vec<i128>,
+}
+
+/// How far apart `from` and `to` are.
+fn gap<T: ListValue>(from: T, to: T) -> i128 {
+    let (from, to): (i128, i128) = (from.into(), to.into());
+    to - from
+}
+
+impl<T: ListValue> Block<T> {
+    fn new(entries: Vec<Entry<T>>) -> Self {
+        let mut block = Block {
+            entries,
+            surplus: 0,
+            levels: Vec::new(),
+            widths: Vec::new(),
+            weighted: Vec::new(),
+        };
+        block.rebuild();
+        block
+    }
+
+    fn first_key(&self) -> T {
+        self.entries[0].key
+    }
+
+    fn last_key(&self) -> T {
+        self.entries[self.entries.len() - 1].key
+    }
+
+    /// Recomputes everything but the entries.
+    fn rebuild(&mut self) {
+        self.surplus = 0;
+        self.levels.clear();
+        for (i, entry) in self.entries.iter().enumerate() {
+            self.surplus += entry.counts[0] as i64 - entry.counts[1] as i64;
+            if let Some(next) = self.entries.get(i + 1) {
+                self.levels.push((self.surplus, gap(entry.key, next.key)));
+            }
+        }
+        self.levels.sort_unstable();
+
+        self.widths.clear();
+        self.weighted.clear();
+        let (mut widths, mut weighted) = (0, 0);
+        self.widths.push(0);
+        self.weighted.push(0);
+        for &(d, width) in &self.levels {
+            widths += width;
+            weighted += d as i128 * width;
+            self.widths.push(widths);
+            self.weighted.push(weighted);
+        }
+    }
+
+    /// The area of the gaps inside the block when `D` is `offset` at its start.
+    fn area(&self, offset: i64) -> i128 {
+        let below = self.levels.partition_point(|&(d, _)| d < -offset);
+        let all = self.levels.len();
+        let offset = offset as i128;
+        let negative = -(offset * self.widths[below] + self.weighted[below]);
+        let positive = offset * (self.widths[all] - self.widths[below]) + self.weighted[all] - self.weighted[below];
+        negative + positive
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PairIndex<T> {
+    /// The distinct values in ascending order, in blocks that are not empty.
+    blocks: Vec<Block<T>>,
+    /// Distinct values over all blocks.
+    entries: usize,
+    /// Values in each list.
+    lens: [usize; 2],
+    distance: Option<i128>,
+    similarity: i128,
+}
+
+impl<T: ListValue> Default for PairIndex<T> {
+    fn default() -> Self {
+        PairIndex::new()
+    }
+}
+
+impl<T: ListValue> PairIndex<T> {
+    pub fn new() -> Self {
+        PairIndex {
+            blocks: Vec::new(),
+            entries: 0,
+            lens: [0, 0],
+            distance: Some(0),
+            similarity: 0,
+        }
+    }
+
+    /// An index holding `left` and `right`, built with one sort.
+    pub fn from_lists(left: &[T], right: &[T]) -> Self {
+        let mut values: Vec<(T, usize)> = left
+            .iter()
+            .map(|&value| (value, 0))
+            .chain(right.iter().map(|&value| (value, 1)))
+            .collect();
+        values.sort_unstable();
+
+        let mut entries: Vec<Entry<T>> = Vec::new();
+        for (key, list) in values {
+            match entries.last_mut() {
+                Some(entry) if entry.key == key => entry.counts[list] += 1,
+                _ => {
+                    let mut counts = [0, 0];
+                    counts[list] = 1;
+                    entries.push(Entry { key, counts });
+                }
+            }
+        }
+
+        let mut index = PairIndex::new();
+        index.entries = entries.len();
+        index.lens = [left.len(), right.len()];
+        index.similarity = entries
+            .iter()
+            .map(|entry| entry.key.into() * entry.counts[0] as i128 * entry.counts[1] as i128)
+            .sum();
+        index.blocks = index.chunked(entries);
+        index.distance = index.compute_distance();
+        index
+    }
+
+    /// The total distance between the lists, paired up smallest to smallest,
+    /// or `None` while they differ in length.
+    pub fn distance(&self) -> Option<i128> {
+        self.distance
+    }
+
+    /// The similarity score: every left value times how often it occurs on the right.
+    pub fn similarity(&self) -> i128 {
+        self.similarity
+    }
+
+    pub fn len(&self, side: Side) -> usize {
+        self.lens[side.index()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    /// How often `value` occurs in the list on `side`.
+    pub fn count(&self, side: Side, value: T) -> usize {
+        let Some(block) = self.blocks.get(self.block_for(value)) else {
+            return 0;
+        };
+        match block.entries.binary_search_by_key(&value, |entry| entry.key) {
+            Ok(at) => block.entries[at].counts[side.index()],
+            Err(_) => 0,
+        }
+    }
+
+    pub fn insert(&mut self, side: Side, value: T) {
+        self.update(side, value, true);
+    }
+
+    /// Removes one occurrence of `value` from the list on `side`, returning
+    /// whether there was one.
+    pub fn remove(&mut self, side: Side, value: T) -> bool {
+        if self.count(side, value) == 0 {
+            return false;
+        }
+        self.update(side, value, false);
+        true
+    }
+
+    /// How many entries a block should hold at the current size.
+    fn block_len(&self) -> usize {
+        self.entries.isqrt().max(MIN_BLOCK_LEN)
+    }
+
+    /// `entries` in blocks of the length for the current size.
+    fn chunked(&self, entries: Vec<Entry<T>>) -> Vec<Block<T>> {
+        entries.chunks(self.block_len()).map(|chunk| Block::new(chunk.to_vec())).collect()
+    }
+
+    /// The block that holds `value` or would: the first one reaching up to
+    /// `value`, or the last one.
+    fn block_for(&self, value: T) -> usize {
+        let block = self.blocks.partition_point(|block| block.last_key() < value);
+        block.min(self.blocks.len().saturating_sub(1))
+    }
+
+    fn update(&mut self, side: Side, value: T, insert: bool) {
+        let b = if self.blocks.is_empty() {
+            self.blocks.push(Block::new(Vec::new()));
+            0
+        } else {
+            self.block_for(value)
+        };
+        let block = &mut self.blocks[b];
+        let at = match block.entries.binary_search_by_key(&value, |entry| entry.key) {
+            Ok(at) => at,
+            Err(at) => {
+                block.entries.insert(at, Entry { key: value, counts: [0, 0] });
+                self.entries += 1;
+                at
+            }
+        };
+
+        let entry = &mut block.entries[at];
+        let other = entry.counts[1 - side.index()] as i128 * value.into();
+        if insert {
+            entry.counts[side.index()] += 1;
+            self.lens[side.index()] += 1;
+            self.similarity += other;
+        } else {
+            entry.counts[side.index()] -= 1;
+            self.lens[side.index()] -= 1;
+            self.similarity -= other;
+        }
+        if entry.counts == [0, 0] {
+            block.entries.remove(at);
+            self.entries -= 1;
+        }
+
+        self.rebalance(b);
+        if self.blocks.len() > 2 * self.entries.div_ceil(self.block_len()) + 1 {
+            let entries = self.blocks.drain(..).flat_map(|block| block.entries).collect();
+            self.blocks = self.chunked(entries);
+        }
+        self.distance = self.compute_distance();
+    }
+
+    /// Brings block `b` up to date after a change, dropping it if it is empty,
+    /// splitting it if it grew too long, or merging it into a neighbour if
+    /// both fit in one block.
+    fn rebalance(&mut self, b: usize) {
+        let block_len = self.block_len();
+        let len = self.blocks[b].entries.len();
+        let neighbour = b.checked_sub(1).into_iter().chain([b + 1]).find(|&n| {
+            self.blocks
+                .get(n)
+                .is_some_and(|neighbour| neighbour.entries.len() + len <= block_len)
+        });
+        if len == 0 {
+            self.blocks.remove(b);
+        } else if len > 2 * block_len {
+            let tail = self.blocks[b].entries.split_off(len / 2);
+            self.blocks[b].rebuild();
+            self.blocks.insert(b + 1, Block::new(tail));
+        } else if let Some(n) = neighbour {
+            let (first, second) = (b.min(n), b.max(n));
+            let moved = self.blocks.remove(second).entries;
+            self.blocks[first].entries.extend(moved);
+            self.blocks[first].rebuild();
+        } else {
+            self.blocks[b].rebuild();
+        }
+    }
+
+    /// Adds up the areas inside every block and in the gaps between them.
+    fn compute_distance(&self) -> Option<i128> {
+        if self.lens[0] != self.lens[1] {
+            return None;
+        }
+        let mut offset = 0;
+        let mut distance = 0;
+        for (b, block) in self.blocks.iter().enumerate() {
+            distance += block.area(offset);
+            offset += block.surplus;
+            if let Some(next) = self.blocks.get(b + 1) {
+                distance += offset.unsigned_abs() as i128 * gap(block.last_key(), next.first_key());
+            }
+        }
+        Some(distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(left: &[i64], right: &[i64]) -> (Option<i128>, i128) {
+        let (mut left, mut right) = (left.to_vec(), right.to_vec());
+        left.sort();
+        right.sort();
+        let distance = (left.len() == right.len())
+            .then(|| left.iter().zip(&right).map(|(l, r)| (l - r).abs() as i128).sum());
+        let similarity = left
+            .iter()
+            .map(|l| *l as i128 * right.iter().filter(|r| *r == l).count() as i128)
+            .sum();
+        (distance, similarity)
+    }
+
+    #[test]
+    fn keeps_example_answers() {
+        let index = PairIndex::from_lists(&[3, 4, 2, 1, 3, 3], &[4, 3, 5, 3, 9, 3]);
+        assert_eq!((index.distance(), index.similarity()), (Some(11), 31));
+        assert_eq!((index.len(Side::Left), index.count(Side::Right, 3)), (6, 3));
+        assert_eq!(PairIndex::from_lists(&[1, 2], &[3]).distance(), None);
+    }
+
+    #[test]
+    fn holds_values_above_i64_max() {
+        let mut index = PairIndex::from_lists(&[u64::MAX, 1], &[1 << 63, u64::MAX]);
+        assert_eq!((index.distance(), index.similarity()), (Some(u64::MAX as i128 - (1 << 63)), u64::MAX as i128));
+        index.insert(Side::Left, 1 << 63);
+        index.insert(Side::Right, 0);
+        assert_eq!(index.distance(), Some(1));
+        assert_eq!((index.similarity(), index.count(Side::Left, u64::MAX)), (u64::MAX as i128 + (1 << 63), 1));
+    }
+
+    #[test]
+    fn follows_inserts_and_removals() {
+        let mut index = PairIndex::new();
+        let mut lists: [Vec<i64>; 2] = [Vec::new(), Vec::new()];
+        let mut state: u64 = 7;
+        for step in 0..1500 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let side = if (state >> 33).is_multiple_of(2) { Side::Left } else { Side::Right };
+            let value = ((state >> 40) % 200) as i64 - 50;
+            let list = &mut lists[side.index()];
+            if (state >> 20).is_multiple_of(3) {
+                let expected = list.iter().position(|&v| v == value).map(|at| list.remove(at));
+                assert_eq!(index.remove(side, value), expected.is_some());
+            } else {
+                list.push(value);
+                index.insert(side, value);
+            }
+            assert_eq!(
+                (index.distance(), index.similarity()),
+                brute_force(&lists[0], &lists[1]),
+                "after step {}",
+                step
+            );
+        }
+        let rebuilt = PairIndex::from_lists(&lists[0], &lists[1]);
+        assert_eq!((rebuilt.distance(), rebuilt.similarity()), (index.distance(), index.similarity()));
+        assert!(!index.remove(Side::Left, 1000));
+    }
+
+    /// An upper bound on the entries and blocks an update goes through: every
+    /// block, and every entry of the longest one.
+    fn update_cost<T: ListValue>(index: &PairIndex<T>) -> usize {
+        let longest = index.blocks.iter().map(|block| block.entries.len()).max().unwrap_or(0);
+        index.blocks.len() + longest
+    }
+
+    /// The highest update cost while `n` pairs are inserted with the surplus
+    /// switching sides after every one, and half of them are removed again
+    /// from the front.
+    fn worst_update(n: i64) -> usize {
+        let mut index = PairIndex::new();
+        let mut worst = 0;
+        for i in 0..n {
+            let (first, second) = if i % 2 == 0 { (Side::Left, Side::Right) } else { (Side::Right, Side::Left) };
+            index.insert(first, 2 * i);
+            index.insert(second, 2 * i + 1);
+            worst = worst.max(update_cost(&index));
+        }
+        for i in 0..n / 2 {
+            assert!(index.remove(Side::Left, 2 * i + i % 2));
+            assert!(index.remove(Side::Right, 2 * i + 1 - i % 2));
+            worst = worst.max(update_cost(&index));
+        }
+        assert_eq!(index.distance(), Some(n as i128 / 2));
+        worst
+    }
+
+    #[test]
+    fn updates_grow_with_the_square_root() {
+        // Sixteen times the values, four times the cost, where a linear
+        // update would take sixteen times as much.
+        let (small, large) = (worst_update(1_000), worst_update(16_000));
+        assert!(large <= 6 * small, "{} then {}", small, large);
+    }
+
+    #[test]
+    fn shrinks_back_after_removals() {
+        let mut index = PairIndex::new();
+        for value in 0..20_000 {
+            index.insert(Side::Left, value);
+            index.insert(Side::Right, value + 1);
+        }
+        let grown = update_cost(&index);
+        // Thin out every block at the same pace, down to every hundredth pair.
+        for step in 1..100 {
+            for value in (step..20_000).step_by(100) {
+                assert!(index.remove(Side::Left, value));
+                assert!(index.remove(Side::Right, value + 1));
+            }
+        }
+        assert_eq!(index.distance(), Some(200));
+        assert!(index.blocks.len() <= 2 * index.entries.div_ceil(index.block_len()) + 1);
+        assert!(update_cost(&index) * 5 < grown, "{} after {}", update_cost(&index), grown);
+    }
+}
@@ -0,0 +1,159 @@
+//! Building blocks for reading puzzle input. Every helper keeps track of
+//! 1-based line numbers so that failures come back as a [`ParseError`]
+//! pointing at the offending token.
+
+use crate::error::{parse_number, ParseError};
+use std::str::FromStr;
+
+/// A line of input together with its 1-based line number.
+pub type Line<'a> = (usize, &'a str);
+
+/// Every line of `input`, numbered from 1.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    (1..).zip(input.lines())
+}
+
+/// The numbered lines of `input`, skipping blank ones.
+pub fn non_blank_lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    numbered_lines(input).filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Groups the lines of `input` into sections separated by one or more blank
+/// lines. Line numbers still refer to the whole input.
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+
+    for (line_no, line) in numbered_lines(input) {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push((line_no, line));
+        }
+    }
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+/// Whitespace-separated integers on one line, which may be empty.
+pub fn integers<T: FromStr>((line_no, line): Line) -> Result<Vec<T>, ParseError> {
+    line.split_whitespace()
+        .map(|token| parse_number(line_no, line, token))
+        .collect()
+}
+
+/// Every line of `input` as a row of whitespace-separated integers. Blank lines
+/// give empty rows.
+pub fn integer_rows<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+    numbered_lines(input).map(integers).collect()
+}
+
+/// Exactly `count` whitespace-separated integers on one line.
+pub fn integer_row<T: FromStr>((line_no, line): Line, count: usize) -> Result<Vec<T>, ParseError> {
+    let mut tokens = line.split_whitespace();
+    let row = (0..count)
+        .map(|index| {
+            let token = tokens
+                .next()
+                .ok_or_else(|| ParseError::end_of_line(line_no, line, format!("column {}", index + 1)))?;
+            parse_number(line_no, line, token)
+        })
+        .collect::<Result<Vec<T>, ParseError>>()?;
+    if let Some(extra) = tokens.next() {
+        return Err(ParseError::at(line_no, line, extra, "end of line"));
+    }
+    Ok(row)
+}
+
+/// Reads `count` whitespace-separated columns of integers, one row per
+/// non-blank line, and returns them column by column.
+pub fn integer_columns<T: FromStr>(input: &str, count: usize) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut columns: Vec<Vec<T>> = (0..count).map(|_| Vec::new()).collect();
+
+    for line in non_blank_lines(input) {
+        for (column, value) in columns.iter_mut().zip(integer_row(line, count)?) {
+            column.push(value);
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Two integers separated by `separator`, such as `47|53`.
+pub fn pair<T: FromStr>((line_no, line): Line, separator: char) -> Result<(T, T), ParseError> {
+    let (first, second) = line
+        .split_once(separator)
+        .ok_or_else(|| ParseError::end_of_line(line_no, line, format!("`{}`", separator)))?;
+    Ok((
+        parse_number(line_no, line, first.trim())?,
+        parse_number(line_no, line, second.trim())?,
+    ))
+}
+
+/// Integers separated by `separator`, such as `75,47,61`.
+pub fn list<T: FromStr>((line_no, line): Line, separator: char) -> Result<Vec<T>, ParseError> {
+    line.split(separator)
+        .map(|token| parse_number(line_no, line, token.trim()))
+        .collect()
+}
+
+/// A `key: values` line, where the values are one or more whitespace-separated
+/// integers.
+pub fn key_values<K: FromStr, V: FromStr>((line_no, line): Line) -> Result<(K, Vec<V>), ParseError> {
+    let (key, values) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::end_of_line(line_no, line, "`:` after the key"))?;
+    let key = parse_number(line_no, line, key.trim())?;
+    let values: Vec<V> = values
+        .split_whitespace()
+        .map(|token| parse_number(line_no, line, token))
+        .collect::<Result<_, _>>()?;
+    if values.is_empty() {
+        return Err(ParseError::end_of_line(line_no, line, "at least one value"));
+    }
+    Ok((key, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sections() {
+        let sections = sections("a\nb\n\n\nc\n\n");
+        assert_eq!(sections, vec![vec![(1, "a"), (2, "b")], vec![(5, "c")]]);
+    }
+
+    #[test]
+    fn reads_columns() {
+        let columns: Vec<Vec<i32>> = integer_columns("1 2\n\n3   4\n", 2).unwrap();
+        assert_eq!(columns, vec![vec![1, 3], vec![2, 4]]);
+
+        let err = integer_columns::<i32>("1 2\n3\n", 2).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 2, "column 2"));
+        let err = integer_columns::<i32>("1 2 3\n", 2).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+    }
+
+    #[test]
+    fn reads_separated_values() {
+        assert_eq!(pair::<i32>((1, "47|53"), '|'), Ok((47, 53)));
+        assert_eq!(list::<i32>((1, "75, 47,61"), ','), Ok(vec![75, 47, 61]));
+
+        let err = list::<i32>((3, "75,x"), ',').unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 4, "`x`"));
+    }
+
+    #[test]
+    fn reads_key_values() {
+        assert_eq!(key_values::<i64, i64>((1, "190: 10 19")), Ok((190, vec![10, 19])));
+
+        let err = key_values::<i64, i64>((2, "190:")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+    }
+}
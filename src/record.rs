@@ -0,0 +1,188 @@
+use crate::bench::Stats;
+use crate::json;
+use crate::memory::AllocStats;
+use crate::solution::{Answer, Part};
+use std::fmt;
+use std::time::Duration;
+
+/// Name used in records for a day's default solver.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// What happened to one day, part and variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    Pass,
+    Fail,
+    Missing,
+    Mismatch,
+    Timeout,
+    Error,
+}
+
+impl Outcome {
+    pub fn is_failure(self) -> bool {
+        matches!(self, Outcome::Fail | Outcome::Mismatch | Outcome::Timeout | Outcome::Error)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Outcome::Ok => "OK",
+            Outcome::Pass => "PASS",
+            Outcome::Fail => "FAIL",
+            Outcome::Missing => "MISSING",
+            Outcome::Mismatch => "MISMATCH",
+            Outcome::Timeout => "TIMEOUT",
+            Outcome::Error => "ERROR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Result of running one day, part and variant, in a form that can be written
+/// out as JSON. Records for a day that failed before solving have no part.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: Option<Part>,
+    pub variant: String,
+    pub answer: Option<Answer>,
+    pub expected: Option<String>,
+    pub time: Option<Duration>,
+    pub runs: usize,
+    pub memory: Option<AllocStats>,
+    pub outcome: Outcome,
+    pub message: Option<String>,
+}
+
+impl Record {
+    /// A solved part, timed by `stats`.
+    pub fn solved(day: u8, part: Part, variant: &str, answer: Answer, stats: &Stats) -> Record {
+        Record {
+            day,
+            part: Some(part),
+            variant: variant.to_string(),
+            answer: Some(answer),
+            expected: None,
+            time: Some(stats.median),
+            runs: stats.runs,
+            memory: None,
+            outcome: Outcome::Ok,
+            message: None,
+        }
+    }
+
+    /// A part that ran past its time `limit`, taking `stats` before it gave up.
+    pub fn timeout(day: u8, part: Part, variant: &str, limit: Duration, stats: &Stats) -> Record {
+        Record {
+            day,
+            part: Some(part),
+            variant: variant.to_string(),
+            answer: None,
+            expected: None,
+            time: Some(stats.median),
+            runs: stats.runs,
+            memory: None,
+            outcome: Outcome::Timeout,
+            message: Some(format!("exceeded the {:.2?} time limit", limit)),
+        }
+    }
+
+    /// A day that could not be solved at all.
+    pub fn error(day: u8, message: String) -> Record {
+        Record {
+            day,
+            part: None,
+            variant: DEFAULT_VARIANT.to_string(),
+            answer: None,
+            expected: None,
+            time: None,
+            runs: 0,
+            memory: None,
+            outcome: Outcome::Error,
+            message: Some(message),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        fn or_null(value: Option<String>) -> String {
+            value.unwrap_or_else(|| "null".to_string())
+        }
+
+        let answer = self.answer.as_ref().map(|answer| match answer {
+            Answer::Int(value) => value.to_string(),
+            Answer::Text(value) => json::string(value),
+        });
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"variant\": {}, \"answer\": {}, \"expected\": {}, \"time_ns\": {}, \"runs\": {}, \"allocations\": {}, \"bytes_allocated\": {}, \"peak_bytes\": {}, \"status\": {}, \"message\": {}}}",
+            self.day,
+            or_null(self.part.map(|part| part.to_string())),
+            json::string(&self.variant),
+            or_null(answer),
+            or_null(self.expected.as_deref().map(json::string)),
+            or_null(self.time.map(|time| time.as_nanos().to_string())),
+            self.runs,
+            or_null(self.memory.map(|memory| memory.allocations.to_string())),
+            or_null(self.memory.map(|memory| memory.bytes.to_string())),
+            or_null(self.memory.map(|memory| memory.peak.to_string())),
+            json::string(&self.outcome.to_string()),
+            or_null(self.message.as_deref().map(json::string)),
+        )
+    }
+}
+
+pub fn print_json(records: &[Record]) {
+    let rows: Vec<String> = records
+        .iter()
+        .map(|record| format!("  {}", record.to_json()))
+        .collect();
+    println!("[\n{}\n]", rows.join(",\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> Stats {
+        Stats::from_samples(vec![Duration::from_nanos(1500)])
+    }
+
+    #[test]
+    fn writes_solved_records() {
+        let mut record = Record::solved(6, Part::Two, "parallel", Answer::Int(-42), &stats());
+        record.memory = Some(AllocStats {
+            allocations: 3,
+            bytes: 96,
+            peak: 64,
+        });
+        assert_eq!(
+            record.to_json(),
+            "{\"day\": 6, \"part\": 2, \"variant\": \"parallel\", \"answer\": -42, \"expected\": null, \
+             \"time_ns\": 1500, \"runs\": 1, \"allocations\": 3, \"bytes_allocated\": 96, \"peak_bytes\": 64, \
+             \"status\": \"OK\", \"message\": null}"
+        );
+    }
+
+    #[test]
+    fn writes_failed_records() {
+        let mut record = Record::solved(3, Part::One, DEFAULT_VARIANT, Answer::Text("a\"b".to_string()), &stats());
+        record.outcome = Outcome::Fail;
+        record.expected = Some("c\\d".to_string());
+        assert_eq!(
+            record.to_json(),
+            "{\"day\": 3, \"part\": 1, \"variant\": \"default\", \"answer\": \"a\\\"b\", \"expected\": \"c\\\\d\", \
+             \"time_ns\": 1500, \"runs\": 1, \"allocations\": null, \"bytes_allocated\": null, \"peak_bytes\": null, \
+             \"status\": \"FAIL\", \"message\": null}"
+        );
+
+        let record = Record::error(1, "aoc1.txt:2:3: expected an integer, found `x`\n".to_string());
+        assert_eq!(
+            record.to_json(),
+            "{\"day\": 1, \"part\": null, \"variant\": \"default\", \"answer\": null, \"expected\": null, \
+             \"time_ns\": null, \"runs\": 0, \"allocations\": null, \"bytes_allocated\": null, \"peak_bytes\": null, \
+             \"status\": \"ERROR\", \"message\": \"aoc1.txt:2:3: expected an integer, found `x`\\n\"}"
+        );
+    }
+}
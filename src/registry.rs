@@ -0,0 +1,23 @@
+use crate::solution::DynSolution;
+use crate::{aoc1, aoc2, aoc3, aoc4, aoc5, aoc6, aoc7};
+
+/// A registered puzzle day.
+pub struct Day {
+    pub number: u8,
+    pub solution: &'static dyn DynSolution,
+}
+
+/// Every implemented day, in order.
+pub static DAYS: &[Day] = &[
+    Day { number: 1, solution: &aoc1::Day1 },
+    Day { number: 2, solution: &aoc2::Day2 },
+    Day { number: 3, solution: &aoc3::Day3 },
+    Day { number: 4, solution: &aoc4::Day4 },
+    Day { number: 5, solution: &aoc5::Day5 },
+    Day { number: 6, solution: &aoc6::Day6 },
+    Day { number: 7, solution: &aoc7::Day7 },
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,134 @@
+//! The Markdown summary written by `report`: one table row per day, part and
+//! variant with the answer, the median time and the speedup over the slowest
+//! variant of the same part.
+
+use crate::error::Error;
+use crate::record::Record;
+use crate::solution::Part;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Markers around the table in a file that holds more than the report, such
+/// as the README. Everything between them is replaced on every write.
+pub const START_MARKER: &str = "<!-- report:start -->";
+pub const END_MARKER: &str = "<!-- report:end -->";
+
+/// Shown instead of the answer with `--redact`.
+const REDACTED: &str = "*redacted*";
+
+/// The report table for `records`. Records without a part, for days that
+/// could not be solved at all, are left out, and parts that timed out show
+/// their outcome without a speedup.
+pub fn markdown(records: &[Record], redact: bool) -> String {
+    let solved_time = |record: &Record| record.answer.as_ref().and(record.time);
+    let slowest = |day: u8, part: Part| {
+        records
+            .iter()
+            .filter(|record| record.day == day && record.part == Some(part))
+            .filter_map(solved_time)
+            .max()
+    };
+
+    let mut table = String::from("| Day | Part | Variant | Answer | Median | Speedup |\n");
+    table.push_str("|----:|-----:|---------|-------:|-------:|--------:|\n");
+    for record in records {
+        let Some(part) = record.part else {
+            continue;
+        };
+        let answer = match &record.answer {
+            Some(_) if redact => REDACTED.to_string(),
+            Some(answer) => answer.to_string(),
+            None => record.outcome.to_string(),
+        };
+        let median = record.time.map(|time| format!("{:.2?}", time)).unwrap_or_default();
+        let speedup = match (solved_time(record), slowest(record.day, part)) {
+            (Some(time), Some(slowest)) if !time.is_zero() => {
+                format!("{:.2}x", slowest.as_secs_f64() / time.as_secs_f64())
+            }
+            _ => String::new(),
+        };
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            record.day, part, record.variant, answer, median, speedup
+        ));
+    }
+    table
+}
+
+/// Replaces whatever is between the report markers in `document` with
+/// `table`, or gives `None` if the markers are missing.
+pub fn splice(document: &str, table: &str) -> Option<String> {
+    let start = document.find(START_MARKER)? + START_MARKER.len();
+    let end = start + document[start..].find(END_MARKER)?;
+    Some(format!("{}\n{}{}", &document[..start], table, &document[end..]))
+}
+
+/// Writes `table` to `path`: between the report markers if the file already
+/// exists, or as the whole file if it does not. An existing file without the
+/// markers is left alone and reported as an error.
+pub fn write(path: &Path, table: &str) -> Result<(), Error> {
+    let io_error = |err: std::io::Error| Error::Io {
+        path: path.display().to_string(),
+        source: err,
+    };
+    let contents = match fs::read_to_string(path) {
+        Ok(document) => splice(&document, table).ok_or_else(|| Error::Config {
+            path: path.display().to_string(),
+            message: format!(
+                "has no `{}` and `{}` lines to write the report between",
+                START_MARKER, END_MARKER
+            ),
+        })?,
+        Err(err) if err.kind() == ErrorKind::NotFound => table.to_string(),
+        Err(err) => return Err(io_error(err)),
+    };
+    fs::write(path, contents).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Stats;
+    use crate::solution::Answer;
+    use std::time::Duration;
+
+    fn timed(part: Part, variant: &str, answer: i64, millis: u64) -> Record {
+        let stats = Stats::from_samples(vec![Duration::from_millis(millis)]);
+        Record::solved(7, part, variant, Answer::Int(answer), &stats)
+    }
+
+    #[test]
+    fn compares_variants_of_the_same_part() {
+        let slow = Stats::from_samples(vec![Duration::from_millis(21)]);
+        let records = vec![
+            timed(Part::One, "default", 3749, 2),
+            timed(Part::Two, "default", 11387, 4),
+            timed(Part::Two, "recursion", 11387, 8),
+            Record::timeout(7, Part::Two, "naive", Duration::from_millis(20), &slow),
+            Record::error(8, "8:1: expected a digit".to_string()),
+        ];
+        assert_eq!(
+            markdown(&records, false),
+            "\
+| Day | Part | Variant | Answer | Median | Speedup |
+|----:|-----:|---------|-------:|-------:|--------:|
+| 7 | 1 | default | 3749 | 2.00ms | 1.00x |
+| 7 | 2 | default | 11387 | 4.00ms | 2.00x |
+| 7 | 2 | recursion | 11387 | 8.00ms | 1.00x |
+| 7 | 2 | naive | TIMEOUT | 21.00ms |  |
+"
+        );
+        assert!(markdown(&records, true).contains("| 7 | 2 | recursion | *redacted* | 8.00ms | 1.00x |"));
+    }
+
+    #[test]
+    fn splices_between_markers() {
+        let readme = "# Title\n\n<!-- report:start -->\nold table\n<!-- report:end -->\n\nMore.\n";
+        assert_eq!(
+            splice(readme, "| new |\n").unwrap(),
+            "# Title\n\n<!-- report:start -->\n| new |\n<!-- report:end -->\n\nMore.\n"
+        );
+        assert_eq!(splice("# Title\n", "| new |\n"), None);
+    }
+}
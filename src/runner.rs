@@ -0,0 +1,963 @@
+//! Runs the registered days the way the command line asks: solving,
+//! verifying, checking variants, benchmarking, reporting and watching, one
+//! day at a time or in parallel, as well as the `new-day`, `fetch`, `submit`,
+//! `matrix` and `stream` subcommands. Every entry point prints its own output
+//! and returns whether it succeeded, so that the binary only parses arguments
+//! and turns the result into an exit code.
+
+use crate::aoc1::{self, Day1, DistanceError, Policy, ReadPolicy};
+use crate::bench::{self, BenchConfig, Measurement, Stats};
+use crate::cancel::{self, Timeouts};
+use crate::client::{Client, Fetch, Submission, Verdict};
+use crate::error::{Error, Overflow, ParseError};
+use crate::memory::{self, AllocStats};
+use crate::record::{self, Outcome, Record, DEFAULT_VARIANT};
+use crate::registry::{self, Day};
+use crate::report;
+use crate::scaffold;
+use crate::solution::{Answer, Part, Solution};
+use crate::store::Store;
+use crate::verify::{ExpectedAnswers, Status};
+use crate::watch::{self, Watcher};
+use rayon::prelude::*;
+use std::any::Any;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Default,
+    Example(String),
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Run,
+    Verify,
+    Bench,
+    CheckVariants,
+    Report,
+}
+
+/// Everything a run was asked to do.
+#[derive(Debug)]
+pub struct Options {
+    pub mode: Mode,
+    pub days: Vec<u8>,
+    pub parts: Vec<Part>,
+    pub input: InputSource,
+    pub store: Store,
+    pub variant: Option<String>,
+    pub compare: bool,
+    /// Thread count for running days in parallel, `Some(0)` lets rayon choose.
+    pub jobs: Option<usize>,
+    pub answers: PathBuf,
+    pub bench_config: BenchConfig,
+    pub format: Format,
+    /// How day 1 treats lines that do not hold two integers.
+    pub read_policy: ReadPolicy<i128>,
+    pub timeouts: Timeouts,
+    pub memory: bool,
+    pub watch: bool,
+    /// Hide the answers in the `report` table.
+    pub redact: bool,
+    /// Where `report` writes its table, stdout if `None`.
+    pub output: Option<PathBuf>,
+}
+
+impl Options {
+    /// A plain run of both parts of every day on the real inputs in `store`.
+    pub fn new(store: Store) -> Self {
+        Options {
+            mode: Mode::Run,
+            days: registry::DAYS.iter().map(|day| day.number).collect(),
+            parts: vec![Part::One, Part::Two],
+            input: InputSource::Default,
+            answers: store.answers_path(),
+            store,
+            variant: None,
+            compare: false,
+            jobs: None,
+            bench_config: BenchConfig::default(),
+            format: Format::Text,
+            read_policy: ReadPolicy::default(),
+            timeouts: Timeouts::default(),
+            memory: false,
+            watch: false,
+            redact: false,
+            output: None,
+        }
+    }
+}
+
+/// The file `--input`, `--example` or the store points at for `day`, if the
+/// input comes from a file at all.
+fn input_file(day: &Day, options: &Options) -> Option<PathBuf> {
+    match &options.input {
+        InputSource::Default => Some(options.store.input_path(day.number, None)),
+        InputSource::Example(name) => Some(options.store.input_path(day.number, Some(name))),
+        InputSource::File(path) => Some(path.clone()),
+        InputSource::Stdin => None,
+    }
+}
+
+fn describe(day: &Day, options: &Options) -> String {
+    match input_file(day, options) {
+        Some(path) => path.display().to_string(),
+        None => "<stdin>".to_string(),
+    }
+}
+
+fn read_input(day: &Day, options: &Options) -> Result<String, Error> {
+    let raw = match input_file(day, options) {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut raw = String::new();
+            io::stdin().read_to_string(&mut raw).map(|_| raw)
+        }
+    };
+    raw.map_err(|err| Error::Io {
+        path: describe(day, options),
+        source: err,
+    })
+}
+
+/// Name of the example input being solved, `None` for the real input.
+fn example_name(options: &Options) -> Option<&str> {
+    match &options.input {
+        InputSource::Example(name) => Some(name),
+        _ => None,
+    }
+}
+
+/// Parses `raw` as the input of `day`, reading day 1 under the read policy.
+/// With `notes`, every line the policy skipped or padded is printed.
+fn parse_raw(day: &Day, options: &Options, raw: &str, notes: bool) -> Result<Box<dyn Any>, ParseError> {
+    let policy = options.read_policy;
+    if day.number != 1 || policy == ReadPolicy::default() {
+        return day.solution.parse(raw);
+    }
+
+    let lists = aoc1::read_lists(raw, policy)?;
+    if notes {
+        let handled = [(&lists.malformed, policy.malformed), (&lists.mismatched, policy.unequal)];
+        for (errors, policy) in handled {
+            let action = if let Policy::Pad(_) = policy { "padded" } else { "skipped" };
+            for err in errors {
+                eprintln!("note: {}:{} ({})", describe(day, options), err, action);
+            }
+        }
+    }
+    let input: <Day1 as Solution>::Input = (lists.left, lists.right);
+    Ok(Box::new(input))
+}
+
+fn parse_input(day: &Day, options: &Options) -> Result<Box<dyn Any>, Error> {
+    let raw = read_input(day, options)?;
+    parse_raw(day, options, &raw, true).map_err(|err| Error::Parse {
+        path: describe(day, options),
+        source: err,
+    })
+}
+
+/// The error for a part of `day` whose answer does not fit its total.
+fn overflow_error<'a>(day: &'a Day, options: &'a Options) -> impl Fn(Overflow) -> Error + 'a {
+    move |err| Error::Overflow {
+        path: describe(day, options),
+        source: err,
+    }
+}
+
+/// Solves `part` with the variant picked on the command line, or the day's default solver.
+fn solve(day: &Day, part: Part, options: &Options, input: &dyn Any) -> Result<Answer, Overflow> {
+    match &options.variant {
+        Some(name) => day
+            .solution
+            .solve_variant(part, name, input)
+            .expect("Variants are validated by the CLI"),
+        None => day.solution.solve(part, input),
+    }
+}
+
+/// Solves `part` with the variant called `name`, which the day has to list.
+fn solve_variant(day: &Day, part: Part, name: &str, input: &dyn Any) -> Result<Answer, Overflow> {
+    day.solution
+        .solve_variant(part, name, input)
+        .expect("Listed variants exist")
+}
+
+/// One part solved once by `solve_within`, or timed by `measure_within`.
+struct Solved {
+    /// The answer, or the time limit if the solver ran past it.
+    answer: Result<Answer, Duration>,
+    stats: Stats,
+    memory: Option<AllocStats>,
+}
+
+impl Solved {
+    fn into_record(self, day: u8, part: Part, variant: &str) -> Record {
+        let mut record = match self.answer {
+            Ok(answer) => Record::solved(day, part, variant, answer, &self.stats),
+            Err(limit) => Record::timeout(day, part, variant, limit, &self.stats),
+        };
+        record.memory = self.memory;
+        record
+    }
+}
+
+/// Runs `f` once with the time limit for `part` of `day`, counting its
+/// allocations if `--memory` asks for them. A part that overflows within the
+/// limit is an error.
+fn solve_within(
+    day: &Day,
+    part: Part,
+    options: &Options,
+    mut f: impl FnMut() -> Result<Answer, Overflow>,
+) -> Result<Solved, Error> {
+    let limit = options.timeouts.limit(day.number, part);
+    let ((answer, memory), stats) = bench::measure(&BenchConfig::once(), || {
+        cancel::with_deadline(limit.map(|limit| Instant::now() + limit), || {
+            if options.memory {
+                memory::measure(&mut f)
+            } else {
+                (f(), None)
+            }
+        })
+    });
+    let answer = match limit {
+        Some(limit) if stats.median >= limit => Err(limit),
+        _ => Ok(answer.map_err(overflow_error(day, options))?),
+    };
+    Ok(Solved { answer, stats, memory })
+}
+
+/// Times `f` with `--warmup` and `--runs`, giving every run the time limit
+/// for `part` of `day` like `solve_within` does. With a limit, one run within
+/// it comes first, so that a part that times out is only run once.
+fn measure_within(
+    day: &Day,
+    part: Part,
+    options: &Options,
+    mut f: impl FnMut() -> Result<Answer, Overflow>,
+) -> Result<Solved, Error> {
+    let limit = options.timeouts.limit(day.number, part);
+    if limit.is_some() {
+        let solved = solve_within(day, part, options, &mut f)?;
+        if solved.answer.is_err() {
+            return Ok(solved);
+        }
+    }
+
+    let mut timed_out = false;
+    let (answer, stats) = bench::measure(&options.bench_config, || {
+        let start = Instant::now();
+        let answer = cancel::with_deadline(limit.map(|limit| start + limit), &mut f);
+        timed_out |= limit.is_some_and(|limit| start.elapsed() >= limit);
+        answer
+    });
+    let answer = match limit {
+        Some(limit) if timed_out => Err(limit),
+        _ => Ok(answer.map_err(overflow_error(day, options))?),
+    };
+    Ok(Solved {
+        answer,
+        stats,
+        memory: None,
+    })
+}
+
+/// ` (N allocations, ...)` when `--memory` counted them, otherwise nothing.
+fn memory_note(memory: &Option<AllocStats>) -> String {
+    match memory {
+        Some(memory) => format!(" ({})", memory),
+        None => String::new(),
+    }
+}
+
+fn variant_label(options: &Options) -> &str {
+    options.variant.as_deref().unwrap_or(DEFAULT_VARIANT)
+}
+
+fn run_day(day: &Day, options: &Options, out: &mut Vec<String>) -> Result<Vec<Record>, Error> {
+    let input = parse_input(day, options)?;
+    let mut records = Vec::new();
+
+    out.push(format!("Solution for Problem {} : ", day.number));
+    for &part in &options.parts {
+        let solved = solve_within(day, part, options, || solve(day, part, options, &*input))?;
+        out.push(match &solved.answer {
+            Ok(answer) => format!("Part {} : {}{}", part, answer, memory_note(&solved.memory)),
+            Err(limit) => format!("Part {} : TIMEOUT (limit {:.2?})", part, limit),
+        });
+        records.push(solved.into_record(day.number, part, variant_label(options)));
+    }
+    if options.compare {
+        for &part in &options.parts {
+            for name in day.solution.variant_names(part) {
+                let solved = measure_within(day, part, options, || solve_variant(day, part, name, &*input))?;
+                out.push(match &solved.answer {
+                    Ok(answer) => format!(
+                        "Part {} [{}] : {}, median {:.2?} over {} runs",
+                        part, name, answer, solved.stats.median, solved.stats.runs
+                    ),
+                    Err(limit) => format!("Part {} [{}] : TIMEOUT (limit {:.2?})", part, name, limit),
+                });
+                records.push(solved.into_record(day.number, part, name));
+            }
+        }
+    }
+    out.push("***************".to_string());
+    Ok(records)
+}
+
+/// Checks every selected part of `day` against the known-good answers.
+fn verify_day(
+    day: &Day,
+    options: &Options,
+    expected: &ExpectedAnswers,
+    out: &mut Vec<String>,
+) -> Result<Vec<Record>, Error> {
+    let input = parse_input(day, options)?;
+    let mut records = Vec::new();
+
+    for &part in &options.parts {
+        let solved = solve_within(day, part, options, || solve(day, part, options, &*input))?;
+        let answer = match &solved.answer {
+            Ok(answer) => answer.clone(),
+            Err(limit) => {
+                out.push(format!("Day {} Part {} : TIMEOUT (limit {:.2?})", day.number, part, limit));
+                records.push(solved.into_record(day.number, part, variant_label(options)));
+                continue;
+            }
+        };
+        let status = expected.check(day.number, example_name(options), part, &answer);
+        let note = memory_note(&solved.memory);
+        out.push(match &status {
+            Status::Pass => format!("Day {} Part {} : {} ({}){}", day.number, part, status, answer, note),
+            Status::Fail { expected } => format!(
+                "Day {} Part {} : {} (expected {}, got {}){}",
+                day.number, part, status, expected, answer, note
+            ),
+            Status::Missing => format!("Day {} Part {} : {} (got {}){}", day.number, part, status, answer, note),
+        });
+
+        let mut record = solved.into_record(day.number, part, variant_label(options));
+        record.outcome = match status {
+            Status::Pass => Outcome::Pass,
+            Status::Fail { expected } => {
+                record.expected = Some(expected);
+                Outcome::Fail
+            }
+            Status::Missing => Outcome::Missing,
+        };
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Runs every variant of the selected parts of `day` against the default solver.
+fn check_variants_day(day: &Day, options: &Options, out: &mut Vec<String>) -> Result<Vec<Record>, Error> {
+    let input = parse_input(day, options)?;
+    let mut records = Vec::new();
+
+    for &part in &options.parts {
+        let solved = solve_within(day, part, options, || day.solution.solve(part, &*input))?;
+        let default_record = solved.into_record(day.number, part, DEFAULT_VARIANT);
+        let Some(expected) = default_record.answer.clone() else {
+            out.push(format!(
+                "Day {} Part {} : TIMEOUT (default solver, {})",
+                day.number,
+                part,
+                default_record.message.as_deref().expect("Timed out records have a message")
+            ));
+            records.push(default_record);
+            continue;
+        };
+        let names = day.solution.variant_names(part);
+        let mut variant_records: Vec<Record> = names
+            .iter()
+            .map(|&name| {
+                let solved = solve_within(day, part, options, || solve_variant(day, part, name, &*input))?;
+                let mut record = solved.into_record(day.number, part, name);
+                if record.answer.as_ref().is_some_and(|answer| *answer != expected) {
+                    record.outcome = Outcome::Mismatch;
+                    record.expected = Some(expected.to_string());
+                }
+                Ok(record)
+            })
+            .collect::<Result<_, Error>>()?;
+        let problems: Vec<&Record> = variant_records
+            .iter()
+            .filter(|record| record.outcome != Outcome::Ok)
+            .collect();
+
+        if names.is_empty() {
+            out.push(format!("Day {} Part {} : OK (no variants)", day.number, part));
+        } else if problems.is_empty() {
+            out.push(format!(
+                "Day {} Part {} : OK ({} variants agree on {})",
+                day.number,
+                part,
+                names.len(),
+                expected
+            ));
+        } else {
+            let outcome = if problems.iter().any(|record| record.outcome == Outcome::Mismatch) {
+                Outcome::Mismatch
+            } else {
+                Outcome::Timeout
+            };
+            out.push(format!("Day {} Part {} : {} (default gives {})", day.number, part, outcome, expected));
+            for record in problems {
+                out.push(match &record.answer {
+                    Some(answer) => format!("    {} gives {}", record.variant, answer),
+                    None => format!(
+                        "    {} {}",
+                        record.variant,
+                        record.message.as_deref().expect("Timed out records have a message")
+                    ),
+                });
+            }
+        }
+        if options.memory {
+            for record in std::iter::once(&default_record).chain(&variant_records) {
+                if let Some(memory) = &record.memory {
+                    out.push(format!("    {} : {}", record.variant, memory));
+                }
+            }
+        }
+
+        records.push(default_record);
+        records.append(&mut variant_records);
+    }
+
+    Ok(records)
+}
+
+fn bench_day(day: &Day, options: &Options) -> Result<Vec<Measurement>, Error> {
+    let config = &options.bench_config;
+    let raw = read_input(day, options)?;
+    let (input, parse_stats) = bench::measure(config, || parse_raw(day, options, &raw, false));
+    let input = input.map_err(|err| Error::Parse {
+        path: describe(day, options),
+        source: err,
+    })?;
+
+    let mut measurements = vec![Measurement {
+        day: day.number,
+        step: "parse".to_string(),
+        stats: parse_stats,
+        timeout: None,
+    }];
+    for &part in &options.parts {
+        let solved = measure_within(day, part, options, || solve(day, part, options, &*input))?;
+        measurements.push(Measurement {
+            day: day.number,
+            step: format!("part {}", part),
+            stats: solved.stats,
+            timeout: solved.answer.err(),
+        });
+    }
+    if options.compare {
+        for &part in &options.parts {
+            for name in day.solution.variant_names(part) {
+                let solved = measure_within(day, part, options, || solve_variant(day, part, name, &*input))?;
+                measurements.push(Measurement {
+                    day: day.number,
+                    step: format!("part {} [{}]", part, name),
+                    stats: solved.stats,
+                    timeout: solved.answer.err(),
+                });
+            }
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// Times the default solver and every variant of the selected parts of `day`
+/// for `report`.
+fn report_day(day: &Day, options: &Options) -> Result<Vec<Record>, Error> {
+    let input = parse_input(day, options)?;
+    let mut records = Vec::new();
+
+    for &part in &options.parts {
+        let solved = measure_within(day, part, options, || day.solution.solve(part, &*input))?;
+        records.push(solved.into_record(day.number, part, DEFAULT_VARIANT));
+        for name in day.solution.variant_names(part) {
+            let solved = measure_within(day, part, options, || solve_variant(day, part, name, &*input))?;
+            records.push(solved.into_record(day.number, part, name));
+        }
+    }
+
+    Ok(records)
+}
+
+/// What one day produced: its text output, its records and how long it took.
+struct DayRun {
+    lines: Vec<String>,
+    records: Vec<Record>,
+    error: Option<String>,
+    elapsed: Duration,
+}
+
+type DayFn<'a> = dyn Fn(&Day, &mut Vec<String>) -> Result<Vec<Record>, Error> + Sync + 'a;
+
+fn run_one(number: u8, f: &DayFn) -> DayRun {
+    let day = registry::find(number).expect("Selected days are validated by the CLI");
+    let start = Instant::now();
+    let mut lines = Vec::new();
+    let (records, error) = match f(day, &mut lines) {
+        Ok(records) => (records, None),
+        Err(err) => (
+            vec![Record::error(day.number, err.to_string())],
+            Some(format!("error: problem {}: {}", day.number, err)),
+        ),
+    };
+
+    DayRun {
+        lines,
+        records,
+        error,
+        elapsed: start.elapsed(),
+    }
+}
+
+fn print_day(options: &Options, day_run: &DayRun) {
+    if options.format == Format::Text {
+        for line in &day_run.lines {
+            println!("{}", line);
+        }
+    }
+    if let Some(error) = &day_run.error {
+        eprintln!("{}", error);
+    }
+}
+
+/// Calls `f` for every selected day and prints what each day produced, in
+/// day order even when the days run in parallel.
+fn collect_records(options: &Options, f: &DayFn) -> Vec<Record> {
+    let start = Instant::now();
+    let day_runs: Vec<DayRun> = match options.jobs {
+        None => options
+            .days
+            .iter()
+            .map(|&number| {
+                let day_run = run_one(number, f);
+                print_day(options, &day_run);
+                day_run
+            })
+            .collect(),
+        Some(jobs) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build()
+                .expect("Unable to start the thread pool");
+            let day_runs: Vec<DayRun> =
+                pool.install(|| options.days.par_iter().map(|&number| run_one(number, f)).collect());
+            for day_run in &day_runs {
+                print_day(options, day_run);
+            }
+            day_runs
+        }
+    };
+
+    if options.jobs.is_some() && options.format == Format::Text {
+        let summed: Duration = day_runs.iter().map(|day_run| day_run.elapsed).sum();
+        println!(
+            "Total : {:.2?} wall-clock, {:.2?} summed over {} days",
+            start.elapsed(),
+            summed,
+            day_runs.len()
+        );
+    }
+    day_runs.into_iter().flat_map(|day_run| day_run.records).collect()
+}
+
+fn run_bench(options: &Options) -> bool {
+    let mut all_ok = true;
+    let mut measurements = Vec::new();
+
+    for &number in &options.days {
+        let day = registry::find(number).expect("Selected days are validated by the CLI");
+        match bench_day(day, options) {
+            Ok(day_measurements) => {
+                all_ok &= day_measurements.iter().all(|measurement| measurement.timeout.is_none());
+                measurements.extend(day_measurements);
+            }
+            Err(err) => {
+                eprintln!("error: problem {}: {}", day.number, err);
+                all_ok = false;
+            }
+        }
+    }
+
+    match options.format {
+        Format::Text => bench::print_table(&measurements),
+        Format::Json => bench::print_json(&measurements),
+    }
+    all_ok
+}
+
+/// Writes the `report` table for the selected days to `--output` or stdout.
+fn run_report(options: &Options) -> bool {
+    let records = collect_records(options, &|day, _| report_day(day, options));
+    let table = report::markdown(&records, options.redact);
+    match &options.output {
+        None => print!("{}", table),
+        Some(path) => match report::write(path, &table) {
+            Ok(()) => println!("Report written to {}", path.display()),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return false;
+            }
+        },
+    }
+    !records.iter().any(|record| record.outcome.is_failure())
+}
+
+/// Runs the selected days, then keeps polling their input files and runs them
+/// again on every change, printing how the answers moved. Never returns.
+fn run_watch(options: &Options) -> ! {
+    let paths = options
+        .days
+        .iter()
+        .filter_map(|&number| {
+            let day = registry::find(number).expect("Selected days are validated by the CLI");
+            input_file(day, options)
+        })
+        .collect();
+    let mut watcher = Watcher::new(paths);
+    let run_all = |options: &Options| collect_records(options, &|day, out| run_day(day, options, out));
+
+    let mut previous = run_all(options);
+    let watched: Vec<String> = watcher.paths().iter().map(|path| path.display().to_string()).collect();
+    println!("Watching {} for changes, press Ctrl-C to stop", watched.join(", "));
+
+    loop {
+        thread::sleep(watch::POLL_INTERVAL);
+        if !watcher.changed() {
+            continue;
+        }
+
+        println!("=============== input changed, running again ===============");
+        let records = run_all(options);
+        let diff = watch::answer_diff(&previous, &records);
+        if diff.is_empty() {
+            println!("No answers changed");
+        } else {
+            println!("Changed answers:");
+            for line in diff {
+                println!("  {}", line);
+            }
+        }
+        previous = records;
+    }
+}
+
+/// Runs the selected days in the selected mode, succeeding if none of them failed.
+pub fn run(options: &Options) -> bool {
+    let records = match options.mode {
+        Mode::Run if options.watch => run_watch(options),
+        Mode::Run => collect_records(options, &|day, out| run_day(day, options, out)),
+        Mode::Verify => {
+            let expected = match ExpectedAnswers::load(&options.answers) {
+                Ok(Some(expected)) => expected,
+                Ok(None) => {
+                    eprintln!(
+                        "note: {} does not exist, every answer is MISSING",
+                        options.answers.display()
+                    );
+                    ExpectedAnswers::default()
+                }
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return false;
+                }
+            };
+            collect_records(options, &|day, out| verify_day(day, options, &expected, out))
+        }
+        Mode::CheckVariants => {
+            collect_records(options, &|day, out| check_variants_day(day, options, out))
+        }
+        Mode::Bench => return run_bench(options),
+        Mode::Report => return run_report(options),
+    };
+
+    if options.format == Format::Json {
+        record::print_json(&records);
+    }
+    !records.iter().any(|record| record.outcome.is_failure())
+}
+
+/// Adds day `number` to the tree in the working directory.
+pub fn new_day(number: u8, store: &Store) -> bool {
+    let added = scaffold::new_day(Path::new("."), store, number);
+    match added {
+        Ok(paths) => {
+            println!("Added day {}:", number);
+            for path in paths {
+                println!("  {}", path.display());
+            }
+            true
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            false
+        }
+    }
+}
+
+/// Downloads the real input for `day` into `store`.
+pub fn fetch(day: u8, store: Store) -> bool {
+    let fetched = Client::from_env(store).and_then(|client| client.fetch_input(day));
+    match fetched {
+        Ok(Fetch { path, downloaded: true }) => println!("Day {} input saved to {}", day, path.display()),
+        Ok(Fetch { path, downloaded: false }) => println!("Day {} input already in {}", day, path.display()),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return false;
+        }
+    }
+    true
+}
+
+/// Prints the day 1 distance and similarity of every pair of columns in `input`.
+pub fn matrix(input: &InputSource, format: Format) -> bool {
+    let (path, raw) = match input {
+        InputSource::File(path) => (path.display().to_string(), fs::read_to_string(path)),
+        _ => {
+            let mut raw = String::new();
+            ("<stdin>".to_string(), io::stdin().read_to_string(&mut raw).map(|_| raw))
+        }
+    };
+    let matrices = raw
+        .map_err(|err| Error::Io {
+            path: path.clone(),
+            source: err,
+        })
+        .and_then(|raw| {
+            aoc1::read_columns::<i128>(&raw).map_err(|err| Error::Parse {
+                path: path.clone(),
+                source: err,
+            })
+        })
+        .and_then(|columns| {
+            aoc1::pairwise(&columns).map_err(|err| match err {
+                DistanceError::Overflow(err) => Error::Overflow {
+                    path: path.clone(),
+                    source: err,
+                },
+                DistanceError::Unequal { .. } => unreachable!("read_columns gives columns of equal length: {}", err),
+            })
+        });
+    match matrices {
+        Ok(matrices) => match format {
+            Format::Text => println!("{}", matrices.table()),
+            Format::Json => println!("{}", matrices.to_json()),
+        },
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return false;
+        }
+    }
+    true
+}
+
+/// Prints both day 1 answers for `input`, reading it line by line and sorting
+/// the columns in chunks of at most `chunk_len` values spilled to disk, so that
+/// the lists never have to fit in memory.
+pub fn stream(input: &InputSource, chunk_len: usize, format: Format) -> bool {
+    let answers = match input {
+        InputSource::File(path) => {
+            let source = path.display().to_string();
+            File::open(path)
+                .map_err(|err| Error::Io {
+                    path: source.clone(),
+                    source: err,
+                })
+                .and_then(|file| aoc1::stream::<i128>(BufReader::new(file), &source, chunk_len))
+        }
+        _ => aoc1::stream::<i128>(io::stdin().lock(), "<stdin>", chunk_len),
+    };
+    match answers {
+        Ok((distance, similarity)) => match format {
+            Format::Text => println!("distance: {}\nsimilarity: {}", distance, similarity),
+            Format::Json => println!("{{\"distance\": {}, \"similarity\": {}}}", distance, similarity),
+        },
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return false;
+        }
+    }
+    true
+}
+
+/// Submits an answer, succeeding only if it is right.
+pub fn submit(day: u8, part: Part, answer: &str, store: Store) -> bool {
+    let submission = Client::from_env(store).and_then(|client| client.submit(day, part, answer));
+    match submission {
+        Ok(Submission { verdict, recorded }) => {
+            let note = if recorded { " (recorded earlier, not sent)" } else { "" };
+            println!("Day {} Part {} : {} is {}{}", day, part, answer, verdict, note);
+            verdict == Verdict::Right
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY1: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    const DAY7: &str = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n";
+
+    /// A store in a fresh temporary directory holding `inputs` as real inputs.
+    fn store(name: &str, inputs: &[(u8, &str)]) -> Store {
+        let root = std::env::temp_dir().join(format!("advent2024-runner-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let store = Store::new(root);
+        for &(day, input) in inputs {
+            fs::write(store.input_path(day, None), input).unwrap();
+        }
+        store
+    }
+
+    fn options(store: Store, days: &[u8]) -> Options {
+        Options {
+            days: days.to_vec(),
+            ..Options::new(store)
+        }
+    }
+
+    fn answers(records: &[Record]) -> Vec<Option<String>> {
+        records.iter().map(|record| record.answer.as_ref().map(Answer::to_string)).collect()
+    }
+
+    #[test]
+    fn runs_a_day() {
+        let options = options(store("run", &[(1, DAY1)]), &[1]);
+        let mut out = Vec::new();
+        let records = run_day(registry::find(1).unwrap(), &options, &mut out).unwrap();
+        assert_eq!(out[1..3], ["Part 1 : 11", "Part 2 : 31"]);
+        assert_eq!(answers(&records), [Some("11".to_string()), Some("31".to_string())]);
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn reads_day_one_under_the_read_policy() {
+        let mut options = options(store("policy", &[(1, "3   4\n4   x\n2\n1   3\n")]), &[1]);
+        let day = registry::find(1).unwrap();
+        assert!(matches!(parse_input(day, &options), Err(Error::Parse { .. })));
+
+        options.read_policy.malformed = Policy::Truncate;
+        options.read_policy.unequal = Policy::Pad(2);
+        let mut out = Vec::new();
+        run_day(day, &options, &mut out).unwrap();
+        assert_eq!(out[1..3], ["Part 1 : 3", "Part 2 : 5"]);
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn verifies_answers() {
+        let options = options(store("verify", &[(1, DAY1)]), &[1]);
+        let expected = ExpectedAnswers::from_toml("[day1]\npart1 = 11\n").unwrap();
+        let mut out = Vec::new();
+        let records = verify_day(registry::find(1).unwrap(), &options, &expected, &mut out).unwrap();
+        assert_eq!(out, ["Day 1 Part 1 : PASS (11)", "Day 1 Part 2 : MISSING (got 31)"]);
+        let outcomes: Vec<Outcome> = records.iter().map(|record| record.outcome).collect();
+        assert_eq!(outcomes, [Outcome::Pass, Outcome::Missing]);
+
+        let expected = ExpectedAnswers::from_toml("[day1]\npart1 = 12\n").unwrap();
+        let records = verify_day(registry::find(1).unwrap(), &options, &expected, &mut Vec::new()).unwrap();
+        assert_eq!((records[0].outcome, records[0].expected.as_deref()), (Outcome::Fail, Some("12")));
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn checks_every_variant() {
+        let options = options(store("variants", &[(7, DAY7)]), &[7]);
+        let day = registry::find(7).unwrap();
+        let mut out = Vec::new();
+        let records = check_variants_day(day, &options, &mut out).unwrap();
+        let variants = day.solution.variant_names(Part::One).len() + day.solution.variant_names(Part::Two).len();
+        assert_eq!(records.len(), 2 + variants);
+        assert!(records.iter().all(|record| record.outcome == Outcome::Ok));
+        assert!(out.iter().all(|line| line.contains(": OK")), "{:?}", out);
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn reports_each_variant() {
+        let mut options = options(store("report", &[(7, DAY7)]), &[7]);
+        options.bench_config = BenchConfig::once();
+        let records = report_day(registry::find(7).unwrap(), &options).unwrap();
+        assert!(records.iter().all(|record| record.answer.is_some()));
+        assert_eq!(records[0].variant, DEFAULT_VARIANT);
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn measures_within_the_time_limit() {
+        let mut options = options(store("limit", &[]), &[6]);
+        let day = registry::find(6).unwrap();
+        options.bench_config = BenchConfig { warmup: 1, runs: 3 };
+        let mut calls = 0;
+        let solved = measure_within(day, Part::One, &options, || {
+            calls += 1;
+            Ok(Answer::Int(41))
+        })
+        .unwrap();
+        assert_eq!((solved.answer.unwrap(), solved.stats.runs, calls), (Answer::Int(41), 3, 4));
+
+        options.timeouts.add("6.1=0.01").unwrap();
+        let mut calls = 0;
+        let solved = measure_within(day, Part::One, &options, || {
+            calls += 1;
+            while !cancel::expired() {}
+            Ok(Answer::Int(0))
+        })
+        .unwrap();
+        assert_eq!((&solved.answer, calls), (&Err(Duration::from_millis(10)), 1));
+        let record = solved.into_record(6, Part::One, DEFAULT_VARIANT);
+        assert_eq!((record.outcome, record.answer), (Outcome::Timeout, None));
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn turns_a_failed_day_into_an_error_record() {
+        let options = options(store("missing", &[]), &[3]);
+        let day_run = run_one(3, &|day, out| run_day(day, &options, out));
+        assert_eq!(day_run.records.len(), 1);
+        assert_eq!(day_run.records[0].outcome, Outcome::Error);
+        assert!(day_run.error.unwrap().starts_with("error: problem 3: "));
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+
+    #[test]
+    fn describes_the_selected_input() {
+        let mut options = options(store("input", &[]), &[1]);
+        assert_eq!(input_file(registry::find(1).unwrap(), &options), Some(options.store.input_path(1, None)));
+        options.input = InputSource::Example("small".to_string());
+        assert_eq!(example_name(&options), Some("small"));
+        options.input = InputSource::Stdin;
+        assert_eq!(describe(registry::find(1).unwrap(), &options), "<stdin>");
+        fs::remove_dir_all(options.store.root()).unwrap();
+    }
+}
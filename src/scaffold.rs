@@ -0,0 +1,225 @@
+//! Support for `new-day`: writes the files for a new day and registers it in
+//! `lib.rs` and `registry.rs`.
+
+use crate::error::Error;
+use crate::store::Store;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn module_template(number: u8) -> String {
+    format!(
+        r#"use crate::error::{{Overflow, ParseError}};
+use crate::parse::non_blank_lines;
+use crate::solution::{{Answer, Solution}};
+
+pub type Input = Vec<String>;
+
+pub fn read_input(input: &str) -> Result<Input, ParseError> {{
+    Ok(non_blank_lines(input).map(|(_, line)| line.to_string()).collect())
+}}
+
+pub struct Day{n};
+
+impl Solution for Day{n} {{
+    type Input = Input;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError> {{
+        read_input(raw)
+    }}
+
+    fn part_one(&self, _input: &Self::Input) -> Result<Answer, Overflow> {{
+        Ok("unsolved".to_string().into())
+    }}
+
+    fn part_two(&self, _input: &Self::Input) -> Result<Answer, Overflow> {{
+        Ok("unsolved".to_string().into())
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use crate::solution::{{assert_example, Part}};
+
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    #[ignore = "fill in the example and its answer"]
+    fn part_one_example() {{
+        assert_example(&Day{n}, EXAMPLE, Part::One, 0);
+    }}
+
+    #[test]
+    #[ignore = "fill in the example and its answer"]
+    fn part_two_example() {{
+        assert_example(&Day{n}, EXAMPLE, Part::Two, 0);
+    }}
+}}
+"#,
+        n = number
+    )
+}
+
+/// Day number of a `pub mod aocN;` line.
+fn module_number(line: &str) -> Option<u8> {
+    line.strip_prefix("pub mod aoc")?.strip_suffix(';')?.parse().ok()
+}
+
+/// Adds `pub mod aocN;` to the source of `lib.rs`, keeping the days in order.
+fn register_module(lib: &str, number: u8) -> Option<String> {
+    let mut lines: Vec<&str> = lib.lines().collect();
+    let last_day = lines.iter().rposition(|line| module_number(line).is_some())?;
+    let at = lines
+        .iter()
+        .position(|line| module_number(line).is_some_and(|day| day > number))
+        .unwrap_or(last_day + 1);
+
+    let new_line = format!("pub mod aoc{};", number);
+    lines.insert(at, &new_line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the `use crate::{...}` import and to `DAYS` in the source
+/// of `registry.rs`, keeping the days in order.
+fn register_day(registry: &str, number: u8) -> Option<String> {
+    let mut lines: Vec<String> = registry.lines().map(str::to_string).collect();
+
+    let import = lines.iter().position(|line| line.starts_with("use crate::{aoc"))?;
+    let modules = lines[import]
+        .strip_prefix("use crate::{")?
+        .strip_suffix("};")?;
+    let mut numbers: Vec<u8> = modules
+        .split(", ")
+        .map(|module| module.strip_prefix("aoc")?.parse().ok())
+        .collect::<Option<_>>()?;
+    numbers.push(number);
+    numbers.sort();
+    let modules: Vec<String> = numbers.iter().map(|n| format!("aoc{}", n)).collect();
+    lines[import] = format!("use crate::{{{}}};", modules.join(", "));
+
+    let entry_number = |line: &str| -> Option<u8> {
+        line.trim_start()
+            .strip_prefix("Day { number: ")?
+            .split(',')
+            .next()?
+            .parse()
+            .ok()
+    };
+    let last_entry = lines.iter().rposition(|line| entry_number(line).is_some())?;
+    let at = lines
+        .iter()
+        .position(|line| entry_number(line).is_some_and(|day| day > number))
+        .unwrap_or(last_entry + 1);
+    lines.insert(
+        at,
+        format!("    Day {{ number: {n}, solution: &aoc{n}::Day{n} }},", n = number),
+    );
+
+    Some(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|err| Error::Io {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    fs::write(path, contents).map_err(|err| Error::Io {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+/// Writes `src/aocN.rs` under `root` and an empty real input in `store`, and
+/// registers the day in `src/lib.rs` and `src/registry.rs`. Returns every
+/// file it created or changed. An existing input is left alone, but an
+/// existing module is an error.
+pub fn new_day(root: &Path, store: &Store, number: u8) -> Result<Vec<PathBuf>, Error> {
+    let module = root.join("src").join(format!("aoc{}.rs", number));
+    let data = store.input_path(number, None);
+    let lib = root.join("src").join("lib.rs");
+    let registry = root.join("src").join("registry.rs");
+
+    if module.exists() {
+        return Err(Error::Config {
+            path: module.display().to_string(),
+            message: "already exists".to_string(),
+        });
+    }
+    let not_found = |path: &Path, what: &str| Error::Config {
+        path: path.display().to_string(),
+        message: format!("unable to find where to register the day: no {}", what),
+    };
+    let new_lib = register_module(&read(&lib)?, number)
+        .ok_or_else(|| not_found(&lib, "`pub mod aocN;` lines"))?;
+    let new_registry = register_day(&read(&registry)?, number)
+        .ok_or_else(|| not_found(&registry, "`use crate::{aocN, ...};` import or `DAYS` entries"))?;
+
+    let mut touched = Vec::new();
+    write(&module, &module_template(number))?;
+    touched.push(module);
+    if !data.exists() {
+        fs::create_dir_all(store.root()).map_err(|err| Error::Io {
+            path: store.root().display().to_string(),
+            source: err,
+        })?;
+        write(&data, "")?;
+        touched.push(data);
+    }
+    write(&lib, &new_lib)?;
+    touched.push(lib);
+    write(&registry, &new_registry)?;
+    touched.push(registry);
+
+    Ok(touched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_module_in_order() {
+        let lib = "//! Docs\n\npub mod aoc1;\npub mod aoc2;\npub mod bench;\n";
+        assert_eq!(
+            register_module(lib, 3).unwrap(),
+            "//! Docs\n\npub mod aoc1;\npub mod aoc2;\npub mod aoc3;\npub mod bench;\n"
+        );
+        assert_eq!(register_module("pub mod bench;\n", 3), None);
+    }
+
+    #[test]
+    fn registers_day_in_order() {
+        let registry = "\
+use crate::{aoc1, aoc3};
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, solution: &aoc1::Day1 },
+    Day { number: 3, solution: &aoc3::Day3 },
+];
+";
+        assert_eq!(
+            register_day(registry, 2).unwrap(),
+            "\
+use crate::{aoc1, aoc2, aoc3};
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, solution: &aoc1::Day1 },
+    Day { number: 2, solution: &aoc2::Day2 },
+    Day { number: 3, solution: &aoc3::Day3 },
+];
+"
+        );
+    }
+
+    #[test]
+    fn registers_the_real_tree() {
+        let lib = register_module(include_str!("lib.rs"), 8).unwrap();
+        let registry = register_day(include_str!("registry.rs"), 8).unwrap();
+        assert!(lib.contains("pub mod aoc8;\n"));
+        assert!(registry.contains("Day { number: 8, solution: &aoc8::Day8 },\n"));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::error::{Overflow, ParseError};
+use std::any::Any;
+use std::fmt;
+
+/// Value produced by one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Int(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+/// Answers too large for an `i64` are kept as text.
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Int(value as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// One of the two halves of a day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A named alternative way of solving one part of a day. Every variant of a
+/// part must agree with [`Solution::part_one`] or [`Solution::part_two`].
+pub struct Variant<I> {
+    pub part: Part,
+    pub name: &'static str,
+    pub solve: fn(&I) -> Result<Answer, Overflow>,
+}
+
+/// A day's puzzle, split into a parse step and the two solving steps so each
+/// of them can be called and timed on its own. Solving fails only when the
+/// answer does not fit the type it is added up in.
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, ParseError>;
+    fn part_one(&self, input: &Self::Input) -> Result<Answer, Overflow>;
+    fn part_two(&self, input: &Self::Input) -> Result<Answer, Overflow>;
+
+    /// Alternative strategies the day has, if any.
+    fn variants(&self) -> Vec<Variant<Self::Input>> {
+        Vec::new()
+    }
+}
+
+/// Type-erased view of a [`Solution`], so days with different input types can
+/// live in the same registry.
+pub trait DynSolution: Sync {
+    fn parse(&self, raw: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn part_one(&self, input: &dyn Any) -> Result<Answer, Overflow>;
+    fn part_two(&self, input: &dyn Any) -> Result<Answer, Overflow>;
+    fn variant_names(&self, part: Part) -> Vec<&'static str>;
+    fn solve_variant(&self, part: Part, name: &str, input: &dyn Any) -> Option<Result<Answer, Overflow>>;
+
+    fn solve(&self, part: Part, input: &dyn Any) -> Result<Answer, Overflow> {
+        match part {
+            Part::One => self.part_one(input),
+            Part::Two => self.part_two(input),
+        }
+    }
+}
+
+fn downcast<T: 'static>(input: &dyn Any) -> &T {
+    input
+        .downcast_ref::<T>()
+        .expect("Input was not produced by this day's parser")
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse(&self, raw: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(Solution::parse(self, raw)?))
+    }
+
+    fn part_one(&self, input: &dyn Any) -> Result<Answer, Overflow> {
+        Solution::part_one(self, downcast::<S::Input>(input))
+    }
+
+    fn part_two(&self, input: &dyn Any) -> Result<Answer, Overflow> {
+        Solution::part_two(self, downcast::<S::Input>(input))
+    }
+
+    fn variant_names(&self, part: Part) -> Vec<&'static str> {
+        self.variants()
+            .iter()
+            .filter(|variant| variant.part == part)
+            .map(|variant| variant.name)
+            .collect()
+    }
+
+    fn solve_variant(&self, part: Part, name: &str, input: &dyn Any) -> Option<Result<Answer, Overflow>> {
+        let variant = self
+            .variants()
+            .into_iter()
+            .find(|variant| variant.part == part && variant.name == name)?;
+        Some((variant.solve)(downcast::<S::Input>(input)))
+    }
+}
+
+/// Parses `raw` with `solution` and checks that `part`, and every variant of
+/// it, gives `expected`.
+#[cfg(test)]
+pub(crate) fn assert_example<S>(solution: &S, raw: &str, part: Part, expected: impl Into<Answer>)
+where
+    S: Solution,
+{
+    let input = solution.parse(raw).expect("Example should parse");
+    let expected = expected.into();
+    let answer = match part {
+        Part::One => solution.part_one(&input),
+        Part::Two => solution.part_two(&input),
+    };
+    assert_eq!(answer, Ok(expected.clone()), "default solver for part {}", part);
+
+    for variant in solution.variants().iter().filter(|variant| variant.part == part) {
+        assert_eq!((variant.solve)(&input), Ok(expected.clone()), "variant `{}` for part {}", variant.name, part);
+    }
+}
@@ -0,0 +1,107 @@
+//! The input store: one directory holding every day's real input, any number
+//! of named example inputs, and the expected answers for all of them.
+//!
+//! ```text
+//! <store>/answers.toml        expected answers, see `verify::ExpectedAnswers`
+//! <store>/aocN.txt            the real input for day N
+//! <store>/aocN/<name>.txt     the example input called `name` for day N
+//! <store>/submissions.toml    verdicts for submitted answers, see `client::Client`
+//! ```
+//!
+//! The store is found through the `AOC_STORE` environment variable, then the
+//! `store` key of the [`Config`] file, and finally falls back to `data` in the
+//! working directory.
+
+use crate::config::Config;
+use crate::error::Error;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the store directory.
+pub const STORE_ENV: &str = "AOC_STORE";
+
+/// Store used when neither the environment nor a config file names one.
+pub const DEFAULT_STORE: &str = "data";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Store { root: root.into() }
+    }
+
+    /// Finds the store from the environment or the config file, as described
+    /// in the module docs. A missing config file is not an error.
+    pub fn locate() -> Result<Self, Error> {
+        if let Some(root) = env::var_os(STORE_ENV) {
+            return Ok(Store::new(root));
+        }
+        let config = Config::load()?;
+        Ok(Store::new(config.store.unwrap_or_else(|| PathBuf::from(DEFAULT_STORE))))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The real input for `day`, or its example called `example`.
+    pub fn input_path(&self, day: u8, example: Option<&str>) -> PathBuf {
+        match example {
+            None => self.root.join(format!("aoc{}.txt", day)),
+            Some(name) => self.root.join(format!("aoc{}", day)).join(format!("{}.txt", name)),
+        }
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.root.join("answers.toml")
+    }
+
+    /// Names of the example inputs stored for `day`, sorted.
+    pub fn examples(&self, day: u8) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.root.join(format!("aoc{}", day))) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "txt" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_out_inputs() {
+        let store = Store::new("/inputs");
+        assert_eq!(store.input_path(6, None), Path::new("/inputs/aoc6.txt"));
+        assert_eq!(store.input_path(6, Some("small")), Path::new("/inputs/aoc6/small.txt"));
+        assert_eq!(store.answers_path(), Path::new("/inputs/answers.toml"));
+    }
+
+    #[test]
+    fn lists_examples() {
+        let root = env::temp_dir().join(format!("advent2024-store-{}", std::process::id()));
+        fs::create_dir_all(root.join("aoc3")).unwrap();
+        fs::write(root.join("aoc3").join("part2.txt"), "").unwrap();
+        fs::write(root.join("aoc3").join("part1.txt"), "").unwrap();
+        fs::write(root.join("aoc3").join("notes.md"), "").unwrap();
+
+        let store = Store::new(&root);
+        assert_eq!(store.examples(3), vec!["part1", "part2"]);
+        assert!(store.examples(4).is_empty());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+use crate::error::Error;
+use crate::solution::{Answer, Part};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Outcome of checking one computed answer against the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail { .. } => write!(f, "FAIL"),
+            Status::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+/// Known-good answers, keyed by day, example name (`None` for the real input)
+/// and part.
+///
+/// The file has one table per day with an entry per part, and a sub-table per
+/// named example, for example
+///
+/// ```toml
+/// [day1]
+/// part1 = 1234
+/// part2 = "5678"
+///
+/// [day1.example]
+/// part1 = 11
+/// part2 = 31
+/// ```
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    answers: HashMap<(u8, Option<String>, Part), String>,
+}
+
+fn parse_day_key(key: &str) -> Option<u8> {
+    key.strip_prefix("day")?.parse().ok()
+}
+
+fn parse_part_key(key: &str) -> Option<Part> {
+    match key {
+        "part1" => Some(Part::One),
+        "part2" => Some(Part::Two),
+        _ => None,
+    }
+}
+
+/// Reads the `partN` entries of one table into `answers`. `path` names the
+/// table in error messages.
+fn read_parts(
+    answers: &mut HashMap<(u8, Option<String>, Part), String>,
+    day: u8,
+    example: Option<&str>,
+    path: &str,
+    parts: &toml::Table,
+) -> Result<(), String> {
+    for (part_key, value) in parts {
+        if let (None, toml::Value::Table(example_parts)) = (example, value) {
+            read_parts(answers, day, Some(part_key), &format!("{}.{}", path, part_key), example_parts)?;
+            continue;
+        }
+        let part = parse_part_key(part_key).ok_or_else(|| {
+            format!("`{}.{}` is not a part, expected `part1` or `part2`", path, part_key)
+        })?;
+        let answer = match value {
+            toml::Value::Integer(value) => value.to_string(),
+            toml::Value::String(value) => value.clone(),
+            _ => {
+                return Err(format!(
+                    "`{}.{}` must be an integer or a string",
+                    path, part_key
+                ))
+            }
+        };
+        answers.insert((day, example.map(str::to_string), part), answer);
+    }
+    Ok(())
+}
+
+fn describe_toml_error(raw: &str, err: &toml::de::Error) -> String {
+    let message = err.message().trim().replace('\n', ": ");
+    match err.span() {
+        Some(span) => {
+            let before = &raw[..span.start];
+            let line = before.matches('\n').count() + 1;
+            let column = before.chars().rev().take_while(|&ch| ch != '\n').count() + 1;
+            format!("{}:{}: {}", line, column, message)
+        }
+        None => message,
+    }
+}
+
+impl ExpectedAnswers {
+    /// Reads the answers file at `path`, or gives `None` if there is none.
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        let describe = || path.display().to_string();
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Config {
+                    path: describe(),
+                    message: format!("unable to read the answers file: {}", err),
+                })
+            }
+        };
+        Self::from_toml(&raw).map(Some).map_err(|message| Error::Config {
+            path: describe(),
+            message,
+        })
+    }
+
+    pub fn from_toml(raw: &str) -> Result<Self, String> {
+        let table: toml::Table = raw.parse().map_err(|err: toml::de::Error| describe_toml_error(raw, &err))?;
+        let mut answers = HashMap::new();
+
+        for (day_key, parts) in &table {
+            let day = parse_day_key(day_key)
+                .ok_or_else(|| format!("`{}` is not a day table, expected `dayN`", day_key))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", day_key))?;
+            read_parts(&mut answers, day, None, day_key, parts)?;
+        }
+
+        Ok(ExpectedAnswers { answers })
+    }
+
+    /// Checks `answer` for the real input of `day`, or for its example called `example`.
+    pub fn check(&self, day: u8, example: Option<&str>, part: Part, answer: &Answer) -> Status {
+        match self.answers.get(&(day, example.map(str::to_string), part)) {
+            None => Status::Missing,
+            Some(expected) if *expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_real_and_example_answers() {
+        let expected = ExpectedAnswers::from_toml(
+            "[day1]\npart1 = 1234\n\n[day1.small]\npart1 = 11\npart2 = \"31\"\n",
+        )
+        .unwrap();
+        assert_eq!(expected.check(1, None, Part::One, &Answer::Int(1234)), Status::Pass);
+        assert_eq!(expected.check(1, None, Part::Two, &Answer::Int(31)), Status::Missing);
+        assert_eq!(expected.check(1, Some("small"), Part::Two, &Answer::Int(31)), Status::Pass);
+        assert_eq!(
+            expected.check(1, Some("small"), Part::One, &Answer::Int(12)),
+            Status::Fail { expected: "11".to_string() }
+        );
+    }
+
+    #[test]
+    fn loads_nothing_without_a_file() {
+        let path = std::env::temp_dir().join("advent2024-no-such-answers.toml");
+        assert!(ExpectedAnswers::load(&path).unwrap().is_none());
+        let err = ExpectedAnswers::load(&std::env::temp_dir()).unwrap_err();
+        assert!(err.to_string().contains("unable to read the answers file"));
+    }
+
+    #[test]
+    fn rejects_nested_examples() {
+        let err = ExpectedAnswers::from_toml("[day1.small.inner]\npart1 = 1\n").unwrap_err();
+        assert_eq!(err, "`day1.small.inner` is not a part, expected `part1` or `part2`");
+    }
+}
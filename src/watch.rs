@@ -0,0 +1,119 @@
+use crate::record::{Record, DEFAULT_VARIANT};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How often `--watch` looks at the input files.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Remembers when a set of files was last modified, so that polling can tell
+/// when any of them changes. A missing file counts as a state of its own, so
+/// creating or deleting one is a change too.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let stamps = stamps(&paths);
+        Watcher { paths, stamps }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Whether any file changed since the last call, or since the watcher was made.
+    pub fn changed(&mut self) -> bool {
+        let stamps = stamps(&self.paths);
+        if stamps == self.stamps {
+            return false;
+        }
+        self.stamps = stamps;
+        true
+    }
+}
+
+fn stamps(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+/// What each record says, keyed by day, part and variant. Records without an
+/// answer show their outcome instead.
+fn summarise(records: &[Record]) -> BTreeMap<(u8, Option<String>, String), String> {
+    records
+        .iter()
+        .map(|record| {
+            let key = (record.day, record.part.map(|part| part.to_string()), record.variant.clone());
+            let value = match &record.answer {
+                Some(answer) => answer.to_string(),
+                None => record.outcome.to_string(),
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+/// One line per answer that differs between two runs, in day and part order.
+pub fn answer_diff(previous: &[Record], current: &[Record]) -> Vec<String> {
+    let previous = summarise(previous);
+    let current = summarise(current);
+    let mut keys: Vec<_> = previous.keys().chain(current.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let (day, part, variant) = key;
+            let mut label = format!("Day {}", day);
+            if let Some(part) = part {
+                label += &format!(" Part {}", part);
+            }
+            if variant != DEFAULT_VARIANT {
+                label += &format!(" [{}]", variant);
+            }
+            match (previous.get(key), current.get(key)) {
+                (Some(old), Some(new)) if old == new => None,
+                (Some(old), Some(new)) => Some(format!("{} : {} -> {}", label, old, new)),
+                (None, Some(new)) => Some(format!("{} : {} (new)", label, new)),
+                (Some(old), None) => Some(format!("{} : {} (gone)", label, old)),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Stats;
+    use crate::solution::{Answer, Part};
+
+    fn solved(day: u8, part: Part, answer: i64) -> Record {
+        let stats = Stats::from_samples(vec![Duration::from_millis(1)]);
+        Record::solved(day, part, DEFAULT_VARIANT, Answer::Int(answer), &stats)
+    }
+
+    #[test]
+    fn diffs_changed_answers_only() {
+        let previous = vec![solved(1, Part::One, 11), solved(1, Part::Two, 30)];
+        let current = vec![solved(1, Part::One, 11), solved(1, Part::Two, 31)];
+        assert_eq!(answer_diff(&previous, &current), vec!["Day 1 Part 2 : 30 -> 31"]);
+        assert!(answer_diff(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn diffs_errors_against_answers() {
+        let previous = vec![Record::error(6, "6:1: expected a guard".to_string())];
+        let current = vec![solved(6, Part::One, 41)];
+        assert_eq!(
+            answer_diff(&previous, &current),
+            vec!["Day 6 : ERROR (gone)", "Day 6 Part 1 : 41 (new)"]
+        );
+    }
+}